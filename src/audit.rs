@@ -0,0 +1,187 @@
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::message::CommitMessage;
+use crate::trailers::DEFAULT_TRAILER_KEY;
+use crate::{Author, GitMob};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+
+/// A commit's attribution, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AuditedCommit {
+    pub id: String,
+    /// When the commit was authored, in the author's time zone
+    pub time: DateTime<FixedOffset>,
+    pub author: Author,
+    pub subject: String,
+    /// The people in its `Co-authored-by` trailers, in order and without duplicates
+    pub coauthors: Vec<AuditedCoauthor>,
+    /// The trailers that are malformed
+    pub problems: Vec<TrailerProblem>,
+}
+
+/// A co-author of a commit, and who they are in the coauthors file
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AuditedCoauthor {
+    #[serde(flatten)]
+    pub author: Author,
+    /// Their initials, if their email is in the coauthors file
+    pub initials: Option<String>,
+    /// Whether their email is in the coauthors file
+    pub resolved: bool,
+}
+
+/// A `Co-authored-by` trailer that isn't the way git and forges expect it
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", content = "trailer", rename_all = "snake_case")]
+pub enum TrailerProblem {
+    /// The key isn't spelled "Co-authored-by", which some forges don't recognize
+    WrongKeyCase(String),
+    /// There's no "<email>", so the co-author can't be credited
+    MissingEmail(String),
+    /// The co-author was already credited by an earlier trailer
+    Duplicate(String),
+}
+
+impl fmt::Display for TrailerProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrailerProblem::WrongKeyCase(trailer) => {
+                write!(
+                    f,
+                    "the key should be \"{DEFAULT_TRAILER_KEY}\": \"{trailer}\""
+                )
+            }
+            TrailerProblem::MissingEmail(trailer) => write!(f, "missing email: \"{trailer}\""),
+            TrailerProblem::Duplicate(trailer) => write!(f, "duplicate: \"{trailer}\""),
+        }
+    }
+}
+
+/// Returns the co-authors in a commit message and the problems with its trailers
+///
+/// The key is matched ignoring case, like git does, and co-authors are matched to the roster by
+/// email, ignoring case.
+fn audit_trailers(
+    message: &str,
+    roster: &LinkedHashMap<String, Author>,
+) -> (Vec<AuditedCoauthor>, Vec<TrailerProblem>) {
+    let mut coauthors = vec![];
+    let mut problems = vec![];
+    let mut seen = HashSet::new();
+
+    for trailer in CommitMessage::parse(message, None).trailers() {
+        if !trailer.has_key(DEFAULT_TRAILER_KEY) {
+            continue;
+        }
+        let line = trailer.to_string();
+        if trailer.key != DEFAULT_TRAILER_KEY {
+            problems.push(TrailerProblem::WrongKeyCase(line.clone()));
+        }
+
+        let Some(author) = trailer.author() else {
+            problems.push(TrailerProblem::MissingEmail(line));
+            continue;
+        };
+        if !seen.insert(author.email.to_lowercase()) {
+            problems.push(TrailerProblem::Duplicate(line));
+            continue;
+        }
+
+        let initials = roster
+            .iter()
+            .find(|(_, coauthor)| coauthor.email.eq_ignore_ascii_case(&author.email))
+            .map(|(initials, _)| initials.to_string());
+        coauthors.push(AuditedCoauthor {
+            author,
+            resolved: initials.is_some(),
+            initials,
+        });
+    }
+
+    (coauthors, problems)
+}
+
+/// Audits the attribution of each commit, in the same order
+pub fn audit_commits(
+    commits: &[CommitInfo],
+    roster: &LinkedHashMap<String, Author>,
+) -> Vec<AuditedCommit> {
+    commits
+        .iter()
+        .map(|commit| {
+            let (coauthors, problems) = audit_trailers(&commit.message, roster);
+            AuditedCommit {
+                id: commit.id.to_string(),
+                time: commit.time,
+                author: commit.author.clone(),
+                subject: CommitMessage::parse(&commit.message, None).title(),
+                coauthors,
+                problems,
+            }
+        })
+        .collect()
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the attribution of each commit in a revision or range, see `get_commits`
+    pub fn get_audit(
+        &self,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<Vec<AuditedCommit>> {
+        let commits = self.get_commits(revision, since, until)?;
+        Ok(audit_commits(&commits, &self.get_all_coauthors()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, get_git_mob};
+
+    #[test]
+    fn test_audit_trailers() {
+        let gm = get_git_mob();
+        let message = "Add feature
+
+co-authored-by: C D <cd@example.com>
+Co-authored-by: E F <ef@example.com>
+Co-authored-by: Cee Dee <CD@example.com>
+Co-authored-by: G H
+Co-authored-by: I J <>
+";
+
+        let (coauthors, problems) = audit_trailers(message, &gm.get_all_coauthors().unwrap());
+        assert_eq!(
+            vec![
+                AuditedCoauthor {
+                    author: author("C D", "cd@example.com"),
+                    initials: Some(String::from("cd")),
+                    resolved: true,
+                },
+                AuditedCoauthor {
+                    author: author("E F", "ef@example.com"),
+                    initials: None,
+                    resolved: false,
+                },
+            ],
+            coauthors
+        );
+        assert_eq!(
+            vec![
+                TrailerProblem::WrongKeyCase(String::from("co-authored-by: C D <cd@example.com>")),
+                TrailerProblem::Duplicate(String::from("Co-authored-by: Cee Dee <CD@example.com>")),
+                TrailerProblem::MissingEmail(String::from("Co-authored-by: G H")),
+                TrailerProblem::MissingEmail(String::from("Co-authored-by: I J <>")),
+            ],
+            problems
+        );
+    }
+}
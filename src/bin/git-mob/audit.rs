@@ -0,0 +1,229 @@
+use crate::mob::format_table;
+use chrono::NaiveDate;
+use clap::{Args, ValueEnum};
+use git_mob_rs::{
+    audit::AuditedCommit, clock::Clock, error::Result, file_actions::FileActions, output::Output,
+    Author, GitMob,
+};
+use serde_json::json;
+
+#[derive(Args)]
+pub struct AuditArgs {
+    /// The revision or range to audit, for example "main..feature"
+    revision: String,
+    /// Only audit commits authored on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    since: Option<NaiveDate>,
+    /// Only audit commits authored on or before this date (YYYY-MM-DD)
+    #[arg(long)]
+    until: Option<NaiveDate>,
+    /// How to print the report
+    #[arg(long, value_enum, default_value_t = AuditFormat::Table)]
+    format: AuditFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum AuditFormat {
+    /// A table of the commits and their co-authors, followed by the malformed trailers
+    Table,
+    /// A row per co-author, or per commit without any, for spreadsheets
+    Csv,
+    /// The same as --json
+    Json,
+}
+
+fn format_person(author: &Author) -> String {
+    format!("{} <{}>", author.name, author.email)
+}
+
+/// Formats the commits as a table with a row per co-author, followed by the malformed trailers
+fn format_audit(commits: &[AuditedCommit]) -> String {
+    let mut rows = vec![];
+    let mut problems = vec![];
+    for commit in commits {
+        let id = commit.id.chars().take(7).collect::<String>();
+        let mut first = [id.clone(), format_person(&commit.author)];
+        if commit.coauthors.is_empty() {
+            rows.push([
+                first[0].clone(),
+                first[1].clone(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        for coauthor in &commit.coauthors {
+            rows.push([
+                std::mem::take(&mut first[0]),
+                std::mem::take(&mut first[1]),
+                format_person(&coauthor.author),
+                coauthor
+                    .initials
+                    .clone()
+                    .unwrap_or_else(|| String::from("(not in the coauthors file)")),
+            ]);
+        }
+        for problem in &commit.problems {
+            problems.push(format!("{id} {problem}\n"));
+        }
+    }
+
+    let mut output = format!("{} commits\n", commits.len());
+    if !rows.is_empty() {
+        output.push('\n');
+        output.push_str(&format_table(
+            ["Commit", "Author", "Co-author", "Initials"],
+            &rows,
+        ));
+    }
+    if !problems.is_empty() {
+        output.push_str("\nMalformed trailers:\n");
+        output.push_str(&problems.concat());
+    }
+    output
+}
+
+/// Quotes a CSV field if it has to be, doubling any quotes in it
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Formats the commits as CSV with a row per co-author, or per commit without any
+fn format_audit_csv(commits: &[AuditedCommit]) -> String {
+    let mut csv = String::from(
+        "commit,time,author_name,author_email,coauthor_name,coauthor_email,initials,resolved,problems\n",
+    );
+    for commit in commits {
+        let time = commit.time.to_rfc3339();
+        let problems = commit
+            .problems
+            .iter()
+            .map(|problem| problem.to_string())
+            .collect::<Vec<String>>()
+            .join("; ");
+        let mut push_row = |coauthor: [&str; 4]| {
+            let mut fields = vec![
+                commit.id.as_str(),
+                &time,
+                &commit.author.name,
+                &commit.author.email,
+            ];
+            fields.extend(coauthor);
+            fields.push(&problems);
+            let row: Vec<String> = fields.into_iter().map(csv_field).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        };
+
+        if commit.coauthors.is_empty() {
+            push_row(["", "", "", ""]);
+        }
+        for coauthor in &commit.coauthors {
+            push_row([
+                &coauthor.author.name,
+                &coauthor.author.email,
+                coauthor.initials.as_deref().unwrap_or_default(),
+                if coauthor.resolved { "true" } else { "false" },
+            ]);
+        }
+    }
+    csv
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    args: AuditArgs,
+    output: Output,
+) -> Result<()> {
+    let commits = gm.get_audit(&args.revision, args.since, args.until)?;
+    let output = match args.format {
+        AuditFormat::Json => Output {
+            json: !output.quiet,
+            ..output
+        },
+        _ => output,
+    };
+    output.print(
+        || {
+            Ok(match args.format {
+                AuditFormat::Csv => format_audit_csv(&commits),
+                _ => format_audit(&commits),
+            })
+        },
+        || Ok(json!({ "commits": commits })),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::DateTime;
+    use git_mob_rs::audit::{AuditedCoauthor, TrailerProblem};
+    use git_mob_rs::test_utils::{author, NOW};
+
+    fn commits() -> Vec<AuditedCommit> {
+        vec![
+            AuditedCommit {
+                id: String::from("1234567890"),
+                time: DateTime::parse_from_rfc3339(NOW).unwrap(),
+                author: author("A B", "ab@example.com"),
+                subject: String::from("Add feature"),
+                coauthors: vec![
+                    AuditedCoauthor {
+                        author: author("C D", "cd@example.com"),
+                        initials: Some(String::from("cd")),
+                        resolved: true,
+                    },
+                    AuditedCoauthor {
+                        author: author("E, F", "ef@example.com"),
+                        initials: None,
+                        resolved: false,
+                    },
+                ],
+                problems: vec![TrailerProblem::MissingEmail(String::from(
+                    "Co-authored-by: G H",
+                ))],
+            },
+            AuditedCommit {
+                id: String::from("abcdef0123"),
+                time: DateTime::parse_from_rfc3339("2024-01-02T09:00:00+01:00").unwrap(),
+                author: author("C D", "cd@example.com"),
+                subject: String::from("Fix"),
+                coauthors: vec![],
+                problems: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_audit() {
+        assert_eq!(
+            "2 commits
+
+Commit   Author                Co-author              Initials
+1234567  A B <ab@example.com>  C D <cd@example.com>   cd
+                               E, F <ef@example.com>  (not in the coauthors file)
+abcdef0  C D <cd@example.com>
+
+Malformed trailers:
+1234567 missing email: \"Co-authored-by: G H\"
+",
+            format_audit(&commits())
+        );
+    }
+
+    #[test]
+    fn test_format_audit_csv() {
+        assert_eq!(
+            "commit,time,author_name,author_email,coauthor_name,coauthor_email,initials,resolved,problems
+1234567890,2024-01-01T09:00:00+00:00,A B,ab@example.com,C D,cd@example.com,cd,true,\"missing email: \"\"Co-authored-by: G H\"\"\"
+1234567890,2024-01-01T09:00:00+00:00,A B,ab@example.com,\"E, F\",ef@example.com,,false,\"missing email: \"\"Co-authored-by: G H\"\"\"
+abcdef0123,2024-01-02T09:00:00+01:00,C D,cd@example.com,,,,,
+",
+            format_audit_csv(&commits())
+        );
+    }
+}
@@ -0,0 +1,759 @@
+use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
+use git_mob_rs::{
+    clock::Clock,
+    completions,
+    error::{GitMobError, Result},
+    file_actions::FileActions,
+    lint::{LintReport, Severity, SCHEMA, SCHEMA_URL},
+    output::Output,
+    Author, GitMob,
+};
+use serde_json::{json, to_string_pretty};
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum CoauthorCommand {
+    /// Adds a coauthor to the coauthors config file.
+    /// For example: git mob coauthor add jd "John Doe" jdoe@example.com
+    #[command(
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true,
+        disable_help_subcommand = true
+    )]
+    Add {
+        #[command(subcommand)]
+        group: Option<AddGroup>,
+        /// The initials of the coauthor, such as jd
+        #[arg(required = true)]
+        initials: Option<String>,
+        /// The name of the coauthor, such as "John Doe"
+        #[arg(required = true)]
+        name: Option<String>,
+        /// The email of the coauthor, such as jdoe@example.com
+        #[arg(required = true)]
+        email: Option<String>,
+    },
+    /// Delete a coauthor from the coauthors config file.
+    /// For example: git mob coauthor delete jd
+    #[command(args_conflicts_with_subcommands = true, disable_help_subcommand = true)]
+    Delete {
+        #[command(subcommand)]
+        group: Option<DeleteGroup>,
+        /// The initials of the coauthor, such as jd
+        #[arg(add = ArgValueCandidates::new(completions::complete_user_initials))]
+        initials: Vec<String>,
+    },
+    /// Edits a coauthor in the coauthors config file.
+    /// For example: git mob coauthor edit jd --name "John Doe" --email jdoe@example.com
+    #[command(
+        args_conflicts_with_subcommands = true,
+        subcommand_negates_reqs = true,
+        disable_help_subcommand = true
+    )]
+    Edit {
+        #[command(subcommand)]
+        group: Option<EditGroup>,
+        /// The initials of the coauthor, such as jd
+        #[arg(
+            required = true,
+            add = ArgValueCandidates::new(completions::complete_user_initials)
+        )]
+        initials: Option<String>,
+        /// The name of the coauthor, such as "John Doe"
+        #[arg(short, long)]
+        name: Option<String>,
+        /// The email of the coauthor, such as jdoe@example.com
+        #[arg(short, long)]
+        email: Option<String>,
+    },
+    /// Open the coauthors config file in the default text editor
+    Open,
+    /// Checks the coauthors config files for mistakes, such as invalid emails or duplicates.
+    /// Checks your own and the repository's file by default
+    Lint {
+        /// The coauthors file to check
+        file: Option<PathBuf>,
+    },
+    /// Prints the JSON schema of the coauthors config file
+    Schema,
+}
+
+#[derive(Subcommand)]
+pub enum AddGroup {
+    /// Adds a group of coauthors, which "git mob" expands to its members, or replaces its
+    /// members if it exists.
+    /// For example: git mob coauthor add group core jd ab
+    Group {
+        /// The name of the group, such as core
+        name: String,
+        /// The initials of its members, such as jd
+        #[arg(required = true, add = ArgValueCandidates::new(completions::complete_initials))]
+        members: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DeleteGroup {
+    /// Deletes a group, but not its members.
+    /// For example: git mob coauthor delete group core
+    Group {
+        /// The name of the group, such as core
+        #[arg(required = true, add = ArgValueCandidates::new(completions::complete_groups))]
+        names: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EditGroup {
+    /// Adds members to a group or removes them from it.
+    /// For example: git mob coauthor edit group core --add jd --remove ab
+    Group {
+        /// The name of the group, such as core
+        #[arg(add = ArgValueCandidates::new(completions::complete_groups))]
+        name: String,
+        /// The initials of the coauthors to add to it
+        #[arg(
+            short,
+            long,
+            num_args = 1..,
+            add = ArgValueCandidates::new(completions::complete_initials)
+        )]
+        add: Vec<String>,
+        /// The initials of the coauthors to remove from it
+        #[arg(
+            short,
+            long,
+            num_args = 1..,
+            add = ArgValueCandidates::new(completions::complete_initials)
+        )]
+        remove: Vec<String>,
+    },
+}
+
+pub trait Coauthor {
+    fn add(&self, initials: &str, name: &str, email: &str) -> Result<String>;
+    fn delete(&self, initials: &[String]) -> Result<String>;
+    fn edit(&self, initials: &str, name: Option<String>, email: Option<String>) -> Result<String>;
+    fn open(&self, output: &Output) -> Result<()>;
+    fn add_group(&self, name: &str, members: &[String]) -> Result<String>;
+    fn delete_groups(&self, names: &[String]) -> Result<String>;
+    fn edit_group(&self, name: &str, add: &[String], remove: &[String]) -> Result<String>;
+}
+
+impl<T: FileActions, C: Clock> Coauthor for GitMob<T, C> {
+    fn add(&self, initials: &str, name: &str, email: &str) -> Result<String> {
+        let coauthors_path = self.get_coauthors_path()?;
+        let coauthors_path = coauthors_path.display();
+
+        let mut coauthors = self.get_user_coauthors()?;
+        coauthors.insert(
+            initials.to_string(),
+            Author {
+                name: name.to_string(),
+                email: email.to_string(),
+            },
+        );
+
+        self.write_coauthors(coauthors)?;
+
+        Ok(format!(
+            "{name} has been added to the {coauthors_path} file"
+        ))
+    }
+
+    fn delete(&self, initials: &[String]) -> Result<String> {
+        let coauthors_path = self.get_coauthors_path()?;
+        let coauthors_path = coauthors_path.display();
+
+        let mut coauthors = self.get_user_coauthors()?;
+        let mut s = String::new();
+        for initial in initials.iter() {
+            coauthors.remove(initial);
+            s.push_str(format!("{initial}: has been removed from {coauthors_path}\n").as_str());
+        }
+
+        self.write_coauthors(coauthors)?;
+
+        // a group shouldn't expand to someone who is gone
+        let mut groups = self.get_user_groups()?;
+        if groups
+            .values()
+            .flatten()
+            .any(|member| initials.contains(member))
+        {
+            for (_, members) in groups.iter_mut() {
+                members.retain(|member| !initials.contains(member));
+            }
+            self.write_groups(groups)?;
+        }
+
+        Ok(s)
+    }
+
+    fn edit(&self, initials: &str, name: Option<String>, email: Option<String>) -> Result<String> {
+        let mut coauthors = self.get_user_coauthors()?;
+        let coauthor = coauthors.get_mut(initials);
+        match coauthor {
+            Some(coauthor) => {
+                if let Some(name) = name {
+                    coauthor.name = name
+                }
+                if let Some(email) = email {
+                    coauthor.email = email
+                }
+            }
+            None => {
+                return Err(GitMobError::UnknownInitials {
+                    initials: initials.to_string(),
+                    coauthors_path: self.get_coauthors_path()?,
+                });
+            }
+        };
+
+        self.write_coauthors(coauthors)?;
+
+        Ok(format!("{initials} has been updated"))
+    }
+
+    fn add_group(&self, name: &str, members: &[String]) -> Result<String> {
+        let coauthors_path = self.get_coauthors_path()?;
+        if self.get_all_coauthors()?.contains_key(name) {
+            return Err(GitMobError::BadRoster {
+                path: coauthors_path,
+                message: format!(
+                    "the group \"{name}\" would be hidden by the co-author with the same initials"
+                ),
+            });
+        }
+        self.check_group_members(members)?;
+
+        let mut groups = self.get_user_groups()?;
+        let mut unique: Vec<String> = vec![];
+        for member in members {
+            if !unique.contains(member) {
+                unique.push(member.clone());
+            }
+        }
+        groups.insert(name.to_string(), unique);
+
+        self.write_groups(groups)?;
+
+        Ok(format!(
+            "{name} has been added to the {} file",
+            coauthors_path.display()
+        ))
+    }
+
+    fn delete_groups(&self, names: &[String]) -> Result<String> {
+        let coauthors_path = self.get_coauthors_path()?;
+        let coauthors_path = coauthors_path.display();
+
+        let mut groups = self.get_user_groups()?;
+        let mut s = String::new();
+        for name in names.iter() {
+            groups.remove(name);
+            s.push_str(format!("{name}: has been removed from {coauthors_path}\n").as_str());
+        }
+
+        self.write_groups(groups)?;
+
+        Ok(s)
+    }
+
+    fn edit_group(&self, name: &str, add: &[String], remove: &[String]) -> Result<String> {
+        let mut groups = self.get_user_groups()?;
+        let members = match groups.get_mut(name) {
+            Some(members) => members,
+            None => {
+                return Err(GitMobError::UnknownGroup {
+                    name: name.to_string(),
+                    coauthors_path: self.get_coauthors_path()?,
+                });
+            }
+        };
+        self.check_group_members(add)?;
+
+        for member in add {
+            if !members.contains(member) {
+                members.push(member.clone());
+            }
+        }
+        members.retain(|member| !remove.contains(member));
+
+        self.write_groups(groups)?;
+
+        Ok(format!("{name} has been updated"))
+    }
+
+    fn open(&self, output: &Output) -> Result<()> {
+        let coauthors_path = self.get_coauthors_path()?;
+
+        // write part of the config for convenience
+        if !coauthors_path.exists() {
+            let s = json!({
+                "$schema": SCHEMA_URL,
+                "coauthors": {
+                    "": {
+                        "name": "",
+                        "email": ""
+                    }
+                }
+            });
+            self.file_actions
+                .write(&coauthors_path, &to_string_pretty(&s).unwrap())
+                .map_err(GitMobError::Io)?;
+        }
+
+        output.print(
+            || {
+                Ok(format!(
+                    "Opening {} in the default text editor...\n",
+                    coauthors_path.display()
+                ))
+            },
+            || Ok(json!({ "file": coauthors_path })),
+        )?;
+
+        open::that(&coauthors_path).map_err(|error| {
+            GitMobError::Io(format!(
+                "Unable to open {}: {error}",
+                coauthors_path.display()
+            ))
+        })
+    }
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    command: CoauthorCommand,
+    output: Output,
+) -> Result<()> {
+    match command {
+        CoauthorCommand::Add {
+            group: Some(AddGroup::Group { name, members }),
+            ..
+        } => {
+            let message = gm.add_group(&name, &members)?;
+            output.print(
+                || Ok(format!("{message}\n")),
+                || gm.get_group_change("added", &[name]),
+            )
+        }
+        CoauthorCommand::Add {
+            initials,
+            name,
+            email,
+            ..
+        } => {
+            // clap requires all three without the group subcommand
+            let initials = initials.expect("initials are required");
+            let name = name.expect("a name is required");
+            let email = email.expect("an email is required");
+            let message = gm.add(&initials, &name, &email)?;
+            output.print(
+                || Ok(format!("{message}\n")),
+                || gm.get_roster_change("added", &[initials]),
+            )
+        }
+        CoauthorCommand::Delete {
+            group: Some(DeleteGroup::Group { names }),
+            ..
+        } => {
+            // the groups have to be looked up before they're removed
+            let change = if output.json {
+                Some(gm.get_group_change("removed", &names)?)
+            } else {
+                None
+            };
+
+            let message = gm.delete_groups(&names)?;
+            output.print(|| Ok(message), || Ok(change))
+        }
+        CoauthorCommand::Delete { initials, .. } => {
+            // the co-authors have to be looked up before they're removed
+            let change = if output.json {
+                Some(gm.get_roster_change("removed", &initials)?)
+            } else {
+                None
+            };
+
+            let message = gm.delete(&initials)?;
+            output.print(|| Ok(message), || Ok(change))
+        }
+        CoauthorCommand::Edit {
+            group: Some(EditGroup::Group { name, add, remove }),
+            ..
+        } => {
+            let message = gm.edit_group(&name, &add, &remove)?;
+            output.print(
+                || Ok(format!("{message}\n")),
+                || gm.get_group_change("updated", &[name]),
+            )
+        }
+        CoauthorCommand::Edit {
+            initials,
+            name,
+            email,
+            ..
+        } => {
+            // clap requires the initials without the group subcommand
+            let initials = initials.expect("initials are required");
+            let message = gm.edit(&initials, name, email)?;
+            output.print(
+                || Ok(format!("{message}\n")),
+                || gm.get_roster_change("updated", &[initials]),
+            )
+        }
+        CoauthorCommand::Open => gm.open(&output),
+        CoauthorCommand::Lint { file } => {
+            let reports = gm.lint_coauthors(file.as_deref())?;
+            output.print(|| Ok(format_lint_reports(&reports)), || Ok(&reports))?;
+
+            let errors: usize = reports
+                .iter()
+                .map(|report| {
+                    report
+                        .issues
+                        .iter()
+                        .filter(|issue| issue.severity == Severity::Error)
+                        .count()
+                })
+                .sum();
+            match reports.iter().find(|report| report.has_errors()) {
+                Some(report) => Err(GitMobError::BadRoster {
+                    path: report.file.clone(),
+                    message: format!(
+                        "found {errors} {}",
+                        if errors == 1 { "error" } else { "errors" }
+                    ),
+                }),
+                None => Ok(()),
+            }
+        }
+        CoauthorCommand::Schema => output.print(
+            || Ok(String::from(SCHEMA)),
+            || {
+                serde_json::from_str::<serde_json::Value>(SCHEMA)
+                    .map_err(|error| GitMobError::Io(format!("Unable to read the schema: {error}")))
+            },
+        ),
+    }
+}
+
+/// Formats the problems like a compiler, "file:line:column: severity: initials: message"
+fn format_lint_reports(reports: &[LintReport]) -> String {
+    let mut s = String::new();
+    for report in reports {
+        let file = report.file.display();
+        if report.issues.is_empty() {
+            s.push_str(&format!("{file}: no problems found\n"));
+        }
+        for issue in &report.issues {
+            let location = match (issue.line, issue.column) {
+                (Some(line), Some(column)) => format!(":{line}:{column}"),
+                (Some(line), None) => format!(":{line}"),
+                _ => String::new(),
+            };
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            let initials = match &issue.initials {
+                Some(initials) => format!("{initials}: "),
+                None => String::new(),
+            };
+            s.push_str(&format!(
+                "{file}{location}: {severity}: {initials}{}\n",
+                issue.message
+            ));
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use git_mob_rs::test_utils::get_git_mob;
+    use linked_hash_map::LinkedHashMap;
+
+    #[test]
+    fn test_add() {
+        let gm = get_git_mob();
+        let coauthors_path = gm.get_coauthors_path().unwrap();
+
+        gm.file_actions.write(&coauthors_path, "").unwrap();
+
+        let mut expected_coauthors = LinkedHashMap::new();
+        expected_coauthors.insert(
+            String::from("ab"),
+            Author {
+                name: String::from("A B"),
+                email: "ab@example.com".to_string(),
+            },
+        );
+
+        assert_eq!(
+            format!(
+                "A B has been added to the {} file",
+                coauthors_path.display()
+            ),
+            gm.add("ab", "A B", "ab@example.com").unwrap()
+        );
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_delete() {
+        let gm = get_git_mob();
+        let coauthors_path = gm.get_coauthors_path().unwrap();
+
+        let coauthors = json!({
+            "coauthors": {
+                "ab": {
+                    "name": "A B",
+                    "email": "ab@example.com"
+                },
+                "cd": {
+                    "name": "C D",
+                    "email": "cd@example.com"
+                },
+                "ef": {
+                    "name": "E F",
+                    "email": "ef@example.com"
+                }
+            }
+        });
+
+        gm.file_actions
+            .write(&coauthors_path, &coauthors.to_string())
+            .unwrap();
+
+        let mut expected_coauthors = LinkedHashMap::new();
+        expected_coauthors.insert(
+            String::from("ab"),
+            Author {
+                name: String::from("A B"),
+                email: "ab@example.com".to_string(),
+            },
+        );
+
+        assert_eq!(
+            format!(
+                "cd: has been removed from {}\nef: has been removed from {}\n",
+                coauthors_path.display(),
+                coauthors_path.display()
+            ),
+            gm.delete(&[String::from("cd"), String::from("ef")])
+                .unwrap()
+        );
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_edit_name_and_email() {
+        let gm = get_git_mob();
+
+        let coauthors = json!({
+            "coauthors": {
+                "ab": {
+                    "name": "A B",
+                    "email": "ab@example.com"
+                },
+                "ef": {
+                    "name": "E F",
+                    "email": "ef@example.com"
+                }
+            }
+        });
+
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), &coauthors.to_string())
+            .unwrap();
+
+        let mut expected_coauthors = LinkedHashMap::new();
+        expected_coauthors.insert(
+            String::from("ab"),
+            Author {
+                name: String::from("C D"),
+                email: "cd@example.com".to_string(),
+            },
+        );
+        expected_coauthors.insert(
+            String::from("ef"),
+            Author {
+                name: String::from("E F"),
+                email: "ef@example.com".to_string(),
+            },
+        );
+
+        assert_eq!(
+            "ab has been updated",
+            gm.edit(
+                &String::from("ab"),
+                Some(String::from("C D")),
+                Some(String::from("cd@example.com")),
+            )
+            .unwrap()
+        );
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_edit_name() {
+        let gm = get_git_mob();
+
+        let coauthors = json!({
+            "coauthors": {
+                "ab": {
+                    "name": "A B",
+                    "email": "ab@example.com"
+                }
+            }
+        });
+
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), &coauthors.to_string())
+            .unwrap();
+
+        let mut expected_coauthors = LinkedHashMap::new();
+        expected_coauthors.insert(
+            String::from("ab"),
+            Author {
+                name: String::from("C D"),
+                email: "ab@example.com".to_string(),
+            },
+        );
+
+        assert_eq!(
+            "ab has been updated",
+            gm.edit(&String::from("ab"), Some(String::from("C D")), None)
+                .unwrap()
+        );
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_edit_email() {
+        let gm = get_git_mob();
+
+        let coauthors = json!({
+            "coauthors": {
+                "ab": {
+                    "name": "A B",
+                    "email": "ab@example.com"
+                }
+            }
+        });
+
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), &coauthors.to_string())
+            .unwrap();
+
+        let mut expected_coauthors = LinkedHashMap::new();
+        expected_coauthors.insert(
+            String::from("ab"),
+            Author {
+                name: String::from("A B"),
+                email: "cd@example.com".to_string(),
+            },
+        );
+
+        assert_eq!(
+            "ab has been updated",
+            gm.edit(
+                &String::from("ab"),
+                None,
+                Some(String::from("cd@example.com"))
+            )
+            .unwrap()
+        );
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_format_lint_reports() {
+        use git_mob_rs::lint::LintIssue;
+
+        let reports = [
+            LintReport {
+                file: PathBuf::from("/home/a/.config/git-coauthors"),
+                issues: vec![
+                    LintIssue {
+                        severity: Severity::Error,
+                        line: Some(3),
+                        column: Some(9),
+                        initials: None,
+                        message: String::from("invalid string"),
+                    },
+                    LintIssue {
+                        severity: Severity::Warning,
+                        line: Some(7),
+                        column: None,
+                        initials: Some(String::from("cd")),
+                        message: String::from("never seen"),
+                    },
+                ],
+            },
+            LintReport {
+                file: PathBuf::from("/repo/.git-coauthors"),
+                issues: vec![],
+            },
+        ];
+
+        assert_eq!(
+            "/home/a/.config/git-coauthors:3:9: error: invalid string\n\
+             /home/a/.config/git-coauthors:7: warning: cd: never seen\n\
+             /repo/.git-coauthors: no problems found\n",
+            format_lint_reports(&reports)
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let gm = get_git_mob();
+        let strings =
+            |strs: &[&str]| -> Vec<String> { strs.iter().map(|s| s.to_string()).collect() };
+
+        gm.add_group("pair", &strings(&["ab", "cd", "ab"])).unwrap();
+        assert_eq!(
+            strings(&["ab", "cd"]),
+            gm.get_user_groups().unwrap()["pair"]
+        );
+        assert!(matches!(
+            gm.add_group("ab", &strings(&["cd"])),
+            Err(GitMobError::BadRoster { .. })
+        ));
+        assert!(matches!(
+            gm.add_group("team", &strings(&["ef"])),
+            Err(GitMobError::UnknownInitials { .. })
+        ));
+
+        assert_eq!(
+            "pair has been updated",
+            gm.edit_group("pair", &[], &strings(&["ab"])).unwrap()
+        );
+        assert_eq!(strings(&["cd"]), gm.get_user_groups().unwrap()["pair"]);
+        assert!(matches!(
+            gm.edit_group("team", &strings(&["ab"]), &[]),
+            Err(GitMobError::UnknownGroup { .. })
+        ));
+
+        // deleting a co-author removes them from their groups
+        gm.delete(&strings(&["cd"])).unwrap();
+        assert!(gm.get_user_groups().unwrap()["pair"].is_empty());
+
+        gm.delete_groups(&strings(&["pair"])).unwrap();
+        assert!(gm.get_user_groups().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_edit_author_who_does_not_exist() {
+        let gm = get_git_mob();
+        assert!(matches!(
+            gm.edit(&String::from("ef"), None, None),
+            Err(GitMobError::UnknownInitials { .. })
+        ));
+    }
+}
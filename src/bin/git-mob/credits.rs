@@ -0,0 +1,153 @@
+use clap::{Args, ValueEnum};
+use git_mob_rs::{
+    clock::Clock, credits::Contributor, error::Result, file_actions::FileActions, output::Output,
+    GitMob,
+};
+use serde_json::json;
+
+#[derive(Args)]
+pub struct CreditsArgs {
+    /// The range of the release, for example "v1.0..v1.1"
+    range: String,
+    /// How to print the contributors
+    #[arg(long, value_enum, default_value_t = CreditsFormat::Markdown)]
+    format: CreditsFormat,
+    /// Link each person's commits, replacing "{id}" with the commit's id.
+    /// For example: https://github.com/owner/repo/commit/{id}
+    #[arg(long, value_name = "URL")]
+    link: Option<String>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum CreditsFormat {
+    /// A markdown list, for release notes
+    Markdown,
+    /// A line per person
+    Text,
+}
+
+/// Formats a person as "Name (initials)", or their name alone if they aren't in the roster
+fn format_contributor(contributor: &Contributor) -> String {
+    match &contributor.initials {
+        Some(initials) => format!("{} ({initials})", contributor.author.name),
+        None => contributor.author.name.to_string(),
+    }
+}
+
+fn format_count(commits: usize) -> String {
+    match commits {
+        1 => String::from("1 commit"),
+        commits => format!("{commits} commits"),
+    }
+}
+
+/// Formats the contributors as a list, most commits first, optionally linking their commits
+fn format_credits(
+    contributors: &[Contributor],
+    format: CreditsFormat,
+    link: Option<&str>,
+) -> String {
+    let mut s = String::new();
+    for contributor in contributors {
+        let name = format_contributor(contributor);
+        let count = format_count(contributor.commits.len());
+        let links: Vec<(String, String)> = match link {
+            Some(link) => contributor
+                .commits
+                .iter()
+                .map(|id| (id.chars().take(7).collect(), link.replace("{id}", id)))
+                .collect(),
+            None => vec![],
+        };
+
+        match format {
+            CreditsFormat::Markdown if links.is_empty() => {
+                s.push_str(&format!("- {name}: {count}\n"))
+            }
+            CreditsFormat::Markdown => {
+                let links: Vec<String> = links
+                    .iter()
+                    .map(|(id, url)| format!("[{id}]({url})"))
+                    .collect();
+                s.push_str(&format!("- {name}: {count} ({})\n", links.join(", ")));
+            }
+            CreditsFormat::Text => {
+                s.push_str(&format!("{name}: {count}\n"));
+                for (_, url) in links {
+                    s.push_str(&format!("    {url}\n"));
+                }
+            }
+        }
+    }
+    s
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    args: CreditsArgs,
+    output: Output,
+) -> Result<()> {
+    let contributors = gm.get_credits(&args.range)?;
+    output.print(
+        || {
+            Ok(format_credits(
+                &contributors,
+                args.format,
+                args.link.as_deref(),
+            ))
+        },
+        || Ok(json!({ "contributors": contributors })),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use git_mob_rs::test_utils::author;
+
+    fn contributors() -> Vec<Contributor> {
+        vec![
+            Contributor {
+                initials: Some(String::from("ab")),
+                author: author("A B", "ab@example.com"),
+                commits: vec![String::from("1234567890"), String::from("abcdef0123")],
+            },
+            Contributor {
+                initials: None,
+                author: author("E F", "ef@example.com"),
+                commits: vec![String::from("abcdef0123")],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_format_credits() {
+        assert_eq!(
+            "- A B (ab): 2 commits\n- E F: 1 commit\n",
+            format_credits(&contributors(), CreditsFormat::Markdown, None)
+        );
+        assert_eq!(
+            "- A B (ab): 2 commits ([1234567](https://example.com/commit/1234567890), [abcdef0](https://example.com/commit/abcdef0123))
+- E F: 1 commit ([abcdef0](https://example.com/commit/abcdef0123))
+",
+            format_credits(
+                &contributors(),
+                CreditsFormat::Markdown,
+                Some("https://example.com/commit/{id}")
+            )
+        );
+        assert_eq!(
+            "A B (ab): 2 commits
+    https://example.com/commit/1234567890
+    https://example.com/commit/abcdef0123
+E F: 1 commit
+    https://example.com/commit/abcdef0123
+",
+            format_credits(
+                &contributors(),
+                CreditsFormat::Text,
+                Some("https://example.com/commit/{id}")
+            )
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use clap::Args;
+use git_mob_rs::{
+    clock::Clock,
+    doctor::{Check, CheckStatus},
+    error::{GitMobError, Result},
+    file_actions::FileActions,
+    output::Output,
+    GitMob,
+};
+use serde_json::json;
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Fix the problems that can be fixed, such as regenerating .gitmessage from .gitinitials
+    #[arg(long)]
+    fix: bool,
+}
+
+/// Formats each check as "[status] name: message", with how to fix a problem below it
+fn format_checks(checks: &[Check]) -> String {
+    let mut s = String::new();
+    for check in checks {
+        let status = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warning => "warning",
+            CheckStatus::Error => "error",
+            CheckStatus::Fixed => "fixed",
+        };
+        s.push_str(&format!("[{status}] {}: {}\n", check.name, check.message));
+        if let Some(hint) = &check.hint {
+            s.push_str(&format!("    {hint}\n"));
+        }
+    }
+    s
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    args: DoctorArgs,
+    output: Output,
+) -> Result<()> {
+    let checks = gm.doctor(args.fix)?;
+    output.print(
+        || Ok(format_checks(&checks)),
+        || Ok(json!({ "checks": checks })),
+    )?;
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Error)
+        .count();
+    match errors {
+        0 => Ok(()),
+        1 => Err(GitMobError::Unhealthy(String::from("Found 1 problem"))),
+        errors => Err(GitMobError::Unhealthy(format!("Found {errors} problems"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_checks() {
+        let checks = [
+            Check {
+                name: "identity",
+                status: CheckStatus::Ok,
+                message: String::from("A B <ab@example.com>"),
+                hint: None,
+            },
+            Check {
+                name: "gitmessage",
+                status: CheckStatus::Error,
+                message: String::from(".git/.gitmessage is missing"),
+                hint: Some(String::from("Run \"git mob doctor --fix\" to fix it")),
+            },
+        ];
+
+        assert_eq!(
+            "[ok] identity: A B <ab@example.com>\n\
+             [error] gitmessage: .git/.gitmessage is missing\n    \
+             Run \"git mob doctor --fix\" to fix it\n",
+            format_checks(&checks)
+        );
+    }
+}
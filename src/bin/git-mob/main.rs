@@ -0,0 +1,469 @@
+mod audit;
+mod coauthor;
+mod credits;
+mod doctor;
+mod mob;
+mod print;
+mod solo;
+mod timer;
+
+use audit::AuditArgs;
+use chrono::NaiveDate;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::{ArgValueCandidates, CompleteEnv};
+use coauthor::CoauthorCommand;
+use credits::CreditsArgs;
+use doctor::DoctorArgs;
+use git_mob_rs::{
+    clock::Clock,
+    completions,
+    error::{GitMobError, Result},
+    file_actions::FileActions,
+    hooks::Hook,
+    links,
+    output::Output,
+    rewrite::RewrittenCommit,
+    roster::RosterFormat,
+    squash::SquashTarget,
+    trailers::TrailerFormat,
+    GitMob,
+};
+use mob::{format_rewritten, Mob};
+use print::PrintArgs;
+use serde_json::json;
+use solo::{Solo, SoloArgs};
+use std::env;
+use std::path::{Path, PathBuf};
+use timer::TimerCommand;
+
+/// Quickly populates the .git/gitmessage template file
+#[derive(Parser)]
+#[command(version, long_about = None, args_conflicts_with_subcommands = true,
+    subcommand_precedence_over_arg = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Who to set as the coauthor, for example "git mob fb ab"
+    #[arg(group = "mob", add = ArgValueCandidates::new(completions::complete_initials))]
+    initials: Vec<String>,
+    /// Choose the co-authors in a fuzzy finder, typing to filter by initials, name or email
+    #[arg(
+        short,
+        long,
+        group = "mob",
+        conflicts_with_all = ["list", "suggest"],
+        hide = cfg!(not(unix))
+    )]
+    interactive: bool,
+    /// Show a list of all co-authors
+    #[arg(short, long)]
+    list: bool,
+    /// Set the mob for every repository, a mob set in a repository still takes precedence
+    #[arg(short, long)]
+    global: bool,
+    /// Suggest co-authors who worked on the staged and modified files
+    #[arg(long, conflicts_with_all = ["initials", "list", "global"])]
+    suggest: bool,
+    /// Use this trailer key instead of the configured mob.trailer, for example "Signed-off-by"
+    #[arg(long, requires = "mob", value_parser = parse_trailer_key)]
+    trailer: Option<String>,
+    /// Use this trailer line instead of the configured mob.trailerFormat, where "{key}",
+    /// "{name}", "{email}" and "{initials}" are replaced
+    #[arg(long, requires = "mob", value_parser = parse_trailer_format)]
+    trailer_format: Option<String>,
+    /// Add the mob's trailers to the last commit, leaving out anyone it already credits
+    #[arg(long, conflicts_with_all = ["rewrite", "list", "suggest"])]
+    amend: bool,
+    /// Add the mob's trailers to a range of commits that aren't on the upstream yet, such as
+    /// "@{u}..HEAD" or "HEAD~3.."
+    #[arg(long, value_name = "RANGE", conflicts_with_all = ["list", "suggest"])]
+    rewrite: Option<String>,
+    #[command(flatten)]
+    output: Output,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Reset back to just yourself (clears the gitmessage template)
+    Solo(SoloArgs),
+    /// Print the .gitmessage template
+    Print(PrintArgs),
+    /// Add, delete or edit co-authors in your coauthors file
+    Coauthor {
+        #[command(subcommand)]
+        command: CoauthorCommand,
+    },
+    /// Manage the hooks: prepare-commit-msg adds the mob's trailers to commits made with
+    /// "git commit -m", amends and GUI clients, commit-msg and pre-push enforce them
+    Hook {
+        #[command(subcommand)]
+        command: HookCommand,
+    },
+    /// Trust the co-authors in this repository's .git-coauthors file, which are ignored until
+    /// trusted and again after every change to it
+    Trust,
+    /// Convert your coauthors file to another format, renaming it to match
+    ConvertCoauthors {
+        /// The format to convert to: json, toml or yaml
+        format: RosterFormat,
+    },
+    /// Print the completion script for a shell, for example:
+    /// "source <(git mob completions bash)"
+    Completions {
+        #[arg(value_parser = ["bash", "zsh", "fish"])]
+        shell: String,
+    },
+    /// Show how often each person and each pair committed together, from the commit authors
+    /// and their Co-authored-by trailers
+    Stats {
+        /// The revision or range to look at, for example "main..feature"
+        #[arg(default_value = "HEAD")]
+        revision: String,
+        /// Only count commits authored on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Only count commits authored on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<NaiveDate>,
+    },
+    /// List each commit in a revision or range with its author and co-authors, whether they're
+    /// in the coauthors file and any malformed trailers
+    Audit(AuditArgs),
+    /// List the contributors to a release range such as "v1.0..v1.1", counting co-authors as
+    /// well as commit authors, for release notes
+    Credits(CreditsArgs),
+    /// Print the trailers for a squash merge of a range such as "main..feature", crediting
+    /// everyone who authored or co-authored its commits except yourself
+    SquashTrailers {
+        /// The range to squash, for example "main..feature"
+        range: String,
+        /// Also add the trailers to the squash message or the mob's template
+        #[arg(long, value_enum)]
+        write: Option<SquashTarget>,
+    },
+    /// Check for the usual reasons mobbing doesn't work, such as another commit.template taking
+    /// precedence or a .gitmessage that doesn't match the mob
+    Doctor(DoctorArgs),
+    /// Rotate the driver of the mob every so often, keeping track of who is driving and who is
+    /// next. Shows who is driving by default
+    Timer {
+        #[command(subcommand)]
+        command: Option<TimerCommand>,
+    },
+    /// Remove the mob from this repository: its files and its commit.template, restoring the
+    /// template that was set before it
+    Uninstall,
+    /// Link git-solo, git-add-coauthor and the other original commands to this binary, which
+    /// runs the matching subcommand based on the name it's run as
+    InstallLinks {
+        /// The directory to put the links in, by default the one this binary is in
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Install hooks, keeping any existing ones.
+    /// For example: git mob hook install commit-msg pre-push
+    Install {
+        /// The hooks to install
+        #[arg(value_enum, default_values_t = [Hook::PrepareCommitMsg])]
+        hooks: Vec<Hook>,
+    },
+    /// Uninstall hooks, restoring any previous ones
+    Uninstall {
+        /// The hooks to uninstall
+        #[arg(value_enum, default_values_t = [Hook::PrepareCommitMsg])]
+        hooks: Vec<Hook>,
+    },
+    /// Run by the installed hook
+    #[command(name = "prepare-commit-msg", hide = true)]
+    PrepareCommitMsg {
+        message_file: PathBuf,
+        source: Option<String>,
+        sha: Option<String>,
+    },
+    /// Run by the installed hook
+    #[command(name = "commit-msg", hide = true)]
+    CommitMsg { message_file: PathBuf },
+    /// Run by the installed hook, with the refs being pushed on stdin
+    #[command(name = "pre-push", hide = true)]
+    PrePush { remote: String, url: Option<String> },
+}
+
+fn run(opts: Cli) -> Result<()> {
+    let gm = GitMob::default();
+    let output = opts.output;
+    let is_rewrite = opts.amend || opts.rewrite.is_some();
+
+    if let Some(command) = opts.command {
+        match command {
+            Command::Solo(args) => solo::run(&gm, args, output)?,
+            Command::Print(args) => print::run(&gm, args, output)?,
+            Command::Coauthor { command } => coauthor::run(&gm, command, output)?,
+            Command::Hook { command } => match command {
+                HookCommand::Install { hooks } => {
+                    let message = hooks
+                        .into_iter()
+                        .map(|hook| gm.install_hook(hook))
+                        .collect::<Result<Vec<String>>>()?
+                        .join("\n");
+                    output.print(
+                        || Ok(format!("{message}\n")),
+                        || Ok(json!({ "message": message })),
+                    )?
+                }
+                HookCommand::Uninstall { hooks } => {
+                    let message = hooks
+                        .into_iter()
+                        .map(|hook| gm.uninstall_hook(hook))
+                        .collect::<Result<Vec<String>>>()?
+                        .join("\n");
+                    output.print(
+                        || Ok(format!("{message}\n")),
+                        || Ok(json!({ "message": message })),
+                    )?
+                }
+                HookCommand::PrepareCommitMsg {
+                    message_file,
+                    source,
+                    ..
+                } => gm.prepare_commit_msg(&message_file, source.as_deref())?,
+                HookCommand::CommitMsg { message_file } => gm.check_commit_msg(&message_file)?,
+                HookCommand::PrePush { remote, .. } => {
+                    let updates = std::io::read_to_string(std::io::stdin())
+                        .map_err(|error| GitMobError::Io(error.to_string()))?;
+                    gm.check_pre_push(&remote, &updates)?
+                }
+            },
+            Command::Trust => {
+                let path = gm.trust_repo_coauthors()?;
+                output.print(
+                    || {
+                        Ok(format!(
+                            "Trusted the co-authors in \"{}\"\n",
+                            path.display()
+                        ))
+                    },
+                    || Ok(json!({ "file": path })),
+                )?;
+            }
+            Command::ConvertCoauthors { format } => {
+                let path = gm.convert_coauthors(format)?;
+                output.print(
+                    || {
+                        let mut message = format!(
+                            "Converted your co-authors to {format} in \"{}\"\n",
+                            path.display()
+                        );
+                        if env::var("GITMOB_COAUTHORS_PATH")
+                            .is_ok_and(|old_path| path != Path::new(&old_path))
+                        {
+                            message.push_str(
+                                "Point GITMOB_COAUTHORS_PATH to the new file to keep using it\n",
+                            );
+                        }
+                        Ok(message)
+                    },
+                    || Ok(json!({ "format": format.to_string(), "file": path })),
+                )?;
+            }
+            Command::Completions { shell } => {
+                completions::write_completions(&shell, &mut std::io::stdout())
+                    .map_err(|error| GitMobError::Io(error.to_string()))?
+            }
+            Command::Stats {
+                revision,
+                since,
+                until,
+            } => output.print(
+                || gm.stats(&revision, since, until),
+                || gm.get_stats(&revision, since, until),
+            )?,
+            Command::Audit(args) => audit::run(&gm, args, output)?,
+            Command::Credits(args) => credits::run(&gm, args, output)?,
+            Command::SquashTrailers { range, write } => {
+                let coauthors = gm.get_squash_coauthors(&range)?;
+                let trailers = gm.get_squash_trailers(&coauthors)?;
+                let file = match write {
+                    Some(target) => Some(gm.write_squash_trailers(&trailers, target)?),
+                    None => None,
+                };
+                output.print(
+                    || {
+                        let mut block: String = trailers
+                            .iter()
+                            .map(|trailer| format!("{trailer}\n"))
+                            .collect();
+                        if let Some(file) = &file {
+                            block.insert_str(
+                                0,
+                                &format!("Added the trailers to {}:\n", file.display()),
+                            );
+                        }
+                        Ok(block)
+                    },
+                    || {
+                        Ok(json!({
+                            "coauthors": coauthors,
+                            "trailers": trailers,
+                            "file": file,
+                        }))
+                    },
+                )?;
+            }
+            Command::Doctor(args) => doctor::run(&gm, args, output)?,
+            Command::Timer { command } => timer::run(&gm, command, output)?,
+            Command::Uninstall => {
+                let removed = gm.uninstall()?;
+                output.print(
+                    || Ok(String::from("Removed the mob from this repository\n")),
+                    || Ok(json!({ "removed": removed })),
+                )?;
+            }
+            Command::InstallLinks { dir } => {
+                let dir = match dir {
+                    Some(dir) => dir,
+                    None => env::current_exe()
+                        .and_then(|exe| exe.canonicalize())
+                        .map_err(|error| GitMobError::Io(error.to_string()))?
+                        .parent()
+                        .map(Path::to_path_buf)
+                        .unwrap_or_default(),
+                };
+                let links = links::install_links(&dir).map_err(|error| {
+                    GitMobError::Io(format!(
+                        "Unable to link the commands in {}: {error}",
+                        dir.display()
+                    ))
+                })?;
+                output.print(
+                    || {
+                        Ok(links
+                            .iter()
+                            .map(|link| format!("Linked {}\n", link.display()))
+                            .collect())
+                    },
+                    || Ok(json!({ "links": links })),
+                )?;
+            }
+        }
+    } else if opts.list {
+        output.print(|| gm.list(), || gm.get_roster_json())?;
+    } else if opts.suggest {
+        output.print(|| gm.suggest(), || gm.get_suggested_coauthors())?;
+    } else if opts.initials.is_empty() && !opts.interactive && is_rewrite {
+        let commits = rewrite_commits(&gm, opts.amend, opts.rewrite.as_deref())?;
+        output.print(
+            || Ok(format_rewritten(&commits)),
+            || Ok(json!({ "mob": gm.get_mob_status()?, "rewritten": commits })),
+        )?;
+    } else if opts.initials.is_empty() && !opts.interactive && opts.global {
+        // the global mob, noting when a mob in this repository takes precedence
+        if let Some(warning) = gm.expire_stale_mob()? {
+            output.warn(&warning);
+        }
+        let trailer = gm.get_trailer_format()?;
+        output.print(
+            || Ok(format!("{}\n", gm.mob_global(&[], &trailer)?)),
+            || gm.get_mob_status(),
+        )?;
+    } else if opts.initials.is_empty() && !opts.interactive {
+        if let Some(warning) = gm.expire_stale_mob()? {
+            output.warn(&warning);
+        }
+        output.print(
+            || Ok(format!("{}\n", gm.get_formatted_gitmessage()?)),
+            || gm.get_mob_status(),
+        )?;
+    } else {
+        let trailer = gm
+            .get_trailer_format()?
+            .with(opts.trailer.as_deref(), opts.trailer_format.as_deref())
+            .map_err(GitMobError::GitConfig)?;
+        if opts.interactive && cfg!(not(unix)) {
+            return Err(GitMobError::Io(String::from(
+                "The interactive picker needs a Unix terminal, set the mob with \"git mob <initials>\" instead",
+            )));
+        }
+        let initials = if opts.interactive {
+            match gm.pick_mob()? {
+                Some(initials) => initials,
+                None => {
+                    output.print(
+                        || Ok(String::from("Cancelled, the mob is unchanged\n")),
+                        || gm.get_mob_status(),
+                    )?;
+                    return Ok(());
+                }
+            }
+        } else {
+            opts.initials
+        };
+        // unselecting everyone in the picker goes solo, unlike "git mob" without initials
+        let message = if opts.interactive && initials.is_empty() {
+            if opts.global {
+                gm.solo_global()?
+            } else {
+                gm.solo()?
+            }
+        } else if opts.global {
+            gm.mob_global(&initials, &trailer)?
+        } else {
+            gm.mob(&initials, &trailer)?
+        };
+        if is_rewrite {
+            let commits = rewrite_commits(&gm, opts.amend, opts.rewrite.as_deref())?;
+            output.print(
+                || Ok(format!("{message}\n{}", format_rewritten(&commits))),
+                || Ok(json!({ "mob": gm.get_mob_status()?, "rewritten": commits })),
+            )?;
+        } else {
+            output.print(|| Ok(format!("{message}\n")), || gm.get_mob_status())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds the mob's trailers to HEAD with `--amend`, or to the range given to `--rewrite`
+fn rewrite_commits<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    amend: bool,
+    range: Option<&str>,
+) -> Result<Vec<RewrittenCommit>> {
+    match range {
+        Some(range) => gm.rewrite_range(range),
+        None if amend => gm.amend_head(),
+        None => Ok(vec![]),
+    }
+}
+
+fn parse_trailer_key(key: &str) -> std::result::Result<String, String> {
+    TrailerFormat::default().with(Some(key), None)?;
+    Ok(key.to_string())
+}
+
+fn parse_trailer_format(format: &str) -> std::result::Result<String, String> {
+    TrailerFormat::default().with(None, Some(format))?;
+    Ok(format.to_string())
+}
+
+fn main() {
+    // "git-solo" and the other original commands are links to this binary
+    let args = links::dispatch_args(env::args_os());
+
+    let current_dir = env::current_dir().ok();
+    let completed = CompleteEnv::with_factory(Cli::command)
+        .try_complete(&args, current_dir.as_deref())
+        .unwrap_or_else(|error| error.exit());
+    if completed {
+        std::process::exit(0);
+    }
+
+    let opts: Cli = Cli::parse_from(args);
+    let output = opts.output;
+
+    run(opts).unwrap_or_else(|error| output.exit(error));
+}
@@ -0,0 +1,360 @@
+use chrono::NaiveDate;
+use git_mob_rs::{
+    clock::Clock, error::Result, file_actions::FileActions, rewrite::RewrittenCommit,
+    roster::RosterSource, stats::PairingStats, trailers::TrailerFormat, GitMob, MobScope,
+};
+
+pub trait Mob {
+    fn mob(&self, users: &[String], trailer: &TrailerFormat) -> Result<String>;
+    fn mob_global(&self, users: &[String], trailer: &TrailerFormat) -> Result<String>;
+    fn list(&self) -> Result<String>;
+    fn suggest(&self) -> Result<String>;
+    fn stats(
+        &self,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<String>;
+}
+
+impl<T: FileActions, C: Clock> Mob for GitMob<T, C> {
+    fn list(&self) -> Result<String> {
+        let coauthors = self.get_all_coauthors_with_source()?;
+        let mut initials: Vec<String> = coauthors
+            .into_iter()
+            .map(|(initials, (author, source))| {
+                let name = author.name;
+                let email = author.email;
+                match source {
+                    RosterSource::User => format!("{initials} {name} <{email}>"),
+                    RosterSource::Repo => format!("{initials} {name} <{email}> ({source})"),
+                }
+            })
+            .collect();
+        let groups = self.get_all_groups()?;
+        if !groups.is_empty() {
+            initials.push(String::from("\nGroups:"));
+            for (name, members) in groups {
+                initials.push(format!("{name}: {}", members.join(", ")));
+            }
+        }
+        if let Some(path) = self.get_untrusted_repo_coauthors_path()? {
+            initials.push(format!(
+                "\nThe co-authors in \"{}\" are ignored until you review them and run \"git mob trust\"",
+                path.display()
+            ));
+        }
+        Ok(format!("{}\n", initials.join("\n")))
+    }
+
+    fn suggest(&self) -> Result<String> {
+        if self.get_changed_paths()?.is_empty() {
+            return Ok(String::from(
+                "There are no staged or modified files to suggest co-authors for\n",
+            ));
+        }
+
+        let suggestions = self.get_suggested_coauthors()?;
+        if suggestions.is_empty() {
+            return Ok(String::from(
+                "No co-authors have worked on the staged or modified files\n",
+            ));
+        }
+
+        let suggestions: Vec<String> = suggestions
+            .into_iter()
+            .map(|suggestion| {
+                let initials = suggestion.initials;
+                let name = suggestion.author.name;
+                let email = suggestion.author.email;
+                let commits = suggestion.commits;
+                let plural = if commits == 1 { "" } else { "s" };
+                format!("{initials} {name} <{email}> ({commits} commit{plural})")
+            })
+            .collect();
+        Ok(format!(
+            "Co-authors who worked on the staged and modified files:\n{}\n",
+            suggestions.join("\n")
+        ))
+    }
+
+    fn mob(&self, initials: &[String], trailer: &TrailerFormat) -> Result<String> {
+        // make sure to not accidentally "solo"
+        if initials.is_empty() {
+            return self.get_formatted_gitmessage();
+        }
+
+        self.write_gitmessage_with_trailer(initials, trailer)?;
+        self.get_formatted_gitmessage()
+    }
+
+    fn mob_global(&self, initials: &[String], trailer: &TrailerFormat) -> Result<String> {
+        if !initials.is_empty() {
+            self.write_global_gitmessage(initials, trailer)?;
+        }
+
+        let formatted_gitmessage = self.get_formatted_gitmessage()?;
+        if self.get_active_scope()? == Some(MobScope::Local) {
+            return Ok(format!(
+                "{formatted_gitmessage}\n\nThe mob set in this repository takes precedence over the global mob"
+            ));
+        }
+        Ok(formatted_gitmessage)
+    }
+
+    fn stats(
+        &self,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<String> {
+        Ok(format_stats(&self.get_stats(revision, since, until)?))
+    }
+}
+
+/// Formats the stats as a table of people followed by a table of pairs
+fn format_stats(stats: &PairingStats) -> String {
+    let people: Vec<[String; 3]> = stats
+        .people
+        .iter()
+        .map(|person| {
+            [
+                person.person.to_string(),
+                person.commits.to_string(),
+                person.paired.to_string(),
+            ]
+        })
+        .collect();
+    let pairs: Vec<[String; 2]> = stats
+        .pairs
+        .iter()
+        .map(|pair| [pair.pair.join(" + "), pair.commits.to_string()])
+        .collect();
+
+    let mut output = format!("{} commits\n", stats.commits);
+    if !people.is_empty() {
+        output.push('\n');
+        output.push_str(&format_table(["Person", "Commits", "Paired"], &people));
+    }
+    if !pairs.is_empty() {
+        output.push('\n');
+        output.push_str(&format_table(["Pair", "Commits"], &pairs));
+    }
+    output
+}
+
+pub fn format_table<const N: usize>(header: [&str; N], rows: &[[String; N]]) -> String {
+    let widths: Vec<usize> = (0..N)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let format_row = |row: Vec<&str>| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        format!("{}\n", cells.join("  ").trim_end())
+    };
+
+    let mut table = format_row(header.to_vec());
+    for row in rows {
+        table.push_str(&format_row(row.iter().map(String::as_str).collect()));
+    }
+    table
+}
+
+/// Formats each commit as "old -> new subject" followed by the trailers it was given
+pub fn format_rewritten(commits: &[RewrittenCommit]) -> String {
+    let mut s = String::new();
+    for commit in commits {
+        let old = &commit.old[..7];
+        let new = &commit.new[..7];
+        let subject = &commit.subject;
+        if commit.old == commit.new {
+            s.push_str(&format!("{old} {subject} (already credits the mob)\n"));
+            continue;
+        }
+        s.push_str(&format!("{old} -> {new} {subject}\n"));
+        for trailer in &commit.added {
+            s.push_str(&format!("    {trailer}\n"));
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use git_mob_rs::{error::GitMobError, test_utils::get_git_mob};
+    use serde_json::json;
+
+    #[test]
+    fn test_mob() {
+        let gm = get_git_mob();
+
+        let authors = "Co-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>";
+
+        assert_eq!(
+            format!("{}\n{}", gm.get_git_user().unwrap(), authors),
+            gm.mob(
+                &[String::from("ab"), String::from("cd")],
+                &TrailerFormat::default()
+            )
+            .unwrap()
+        );
+        assert_eq!(format!("\n\n{}", authors), gm.get_gitmessage().unwrap());
+
+        // make sure empty vec doesn't reset gitmessage file
+        assert_eq!(
+            format!("{}\n{}", gm.get_git_user().unwrap(), authors),
+            gm.mob(&[], &TrailerFormat::default()).unwrap()
+        );
+        assert_eq!(format!("\n\n{}", authors), gm.get_gitmessage().unwrap());
+    }
+
+    #[test]
+    fn test_mob_global_status() {
+        let gm = get_git_mob();
+        gm.mob(&[String::from("ab")], &TrailerFormat::default())
+            .unwrap();
+
+        // showing the global mob without initials notes that the local one is used instead
+        assert_eq!(
+            format!(
+                "{}\nCo-authored-by: A B <ab@example.com>\n\n\
+                 The mob set in this repository takes precedence over the global mob",
+                gm.get_git_user().unwrap()
+            ),
+            gm.mob_global(&[], &TrailerFormat::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        let gm = get_git_mob();
+
+        let author1 = "ab A B <ab@example.com>";
+        let author2 = "cd C D <cd@example.com>";
+
+        assert_eq!(format!("{}\n{}\n", author1, author2), gm.list().unwrap());
+
+        gm.file_actions
+            .write(
+                &gm.get_repo_coauthors_path().unwrap().unwrap(),
+                &json!({
+                    "coauthors": {
+                        "ef": {
+                            "name": "E F",
+                            "email": "ef@example.com"
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+        assert!(gm
+            .list()
+            .unwrap()
+            .ends_with("are ignored until you review them and run \"git mob trust\"\n"));
+    }
+
+    #[test]
+    fn test_format_stats() {
+        use git_mob_rs::stats::{PairStats, PersonStats};
+
+        let stats = PairingStats {
+            commits: 3,
+            people: vec![
+                PersonStats {
+                    person: String::from("ab"),
+                    commits: 3,
+                    paired: 2,
+                },
+                PersonStats {
+                    person: String::from("ef@example.com"),
+                    commits: 2,
+                    paired: 2,
+                },
+            ],
+            pairs: vec![PairStats {
+                pair: [String::from("ab"), String::from("ef@example.com")],
+                commits: 2,
+            }],
+        };
+
+        assert_eq!(
+            "3 commits
+
+Person          Commits  Paired
+ab              3        2
+ef@example.com  2        2
+
+Pair                 Commits
+ab + ef@example.com  2
+",
+            format_stats(&stats)
+        );
+    }
+
+    #[test]
+    fn test_format_rewritten() {
+        let commits = [
+            RewrittenCommit {
+                old: String::from("1111111aaaa"),
+                new: String::from("2222222bbbb"),
+                subject: String::from("Add a feature"),
+                added: vec![String::from("Co-authored-by: C D <cd@example.com>")],
+            },
+            RewrittenCommit {
+                old: String::from("3333333cccc"),
+                new: String::from("3333333cccc"),
+                subject: String::from("Fix it"),
+                added: vec![],
+            },
+        ];
+
+        assert_eq!(
+            "1111111 -> 2222222 Add a feature\n    \
+             Co-authored-by: C D <cd@example.com>\n\
+             3333333 Fix it (already credits the mob)\n",
+            format_rewritten(&commits)
+        );
+    }
+
+    #[test]
+    fn test_mob_empty_authors() {
+        let gm = get_git_mob();
+        assert!(matches!(
+            gm.mob(&[String::from("ef")], &TrailerFormat::default()),
+            Err(GitMobError::UnknownInitials { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mob_no_authors() {
+        let gm = get_git_mob();
+
+        gm.file_actions
+            .write(
+                &gm.get_coauthors_path().unwrap(),
+                &json!({
+                    "coauthors": {
+                    }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            gm.mob(&[String::from("ab")], &TrailerFormat::default()),
+            Err(GitMobError::UnknownInitials { .. })
+        ));
+    }
+}
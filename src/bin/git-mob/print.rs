@@ -0,0 +1,134 @@
+use clap::Args;
+use git_mob_rs::{clock::Clock, error::Result, file_actions::FileActions, output::Output, GitMob};
+use serde_json::json;
+
+#[derive(Args)]
+pub struct PrintArgs {
+    /// Prints a comma separated list of selected co-author initials
+    #[arg(short, long)]
+    initials: bool,
+    /// Prints where the mob is set, either "local" or "global"
+    #[arg(short, long)]
+    scope: bool,
+    /// Prints the initials of whoever is driving, when the rotation timer is running
+    #[arg(short, long)]
+    driver: bool,
+}
+
+pub trait Print {
+    fn print(&self) -> Result<String>;
+    fn print_initials(&self) -> Result<String>;
+    fn print_scope(&self) -> Result<String>;
+    fn print_driver(&self) -> Result<String>;
+}
+
+impl<T: FileActions, C: Clock> Print for GitMob<T, C> {
+    fn print(&self) -> Result<String> {
+        Ok(format!("{}\n", self.get_gitmessage()?))
+    }
+
+    fn print_initials(&self) -> Result<String> {
+        self.get_gitinitials()
+    }
+
+    fn print_scope(&self) -> Result<String> {
+        Ok(match self.get_active_scope()? {
+            Some(scope) => format!("{scope}\n"),
+            None => String::new(),
+        })
+    }
+
+    fn print_driver(&self) -> Result<String> {
+        Ok(match self.get_rotation()? {
+            Some(rotation) => format!("{}\n", rotation.driver),
+            None => String::new(),
+        })
+    }
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    args: PrintArgs,
+    output: Output,
+) -> Result<()> {
+    // this runs in shell prompts, so an expired mob is only warned about and never ended
+    if let Some(warning) = gm.get_stale_mob_warning()? {
+        output.warn(&warning);
+    }
+
+    if args.initials {
+        output.print(
+            || gm.print_initials(),
+            || Ok(json!({ "initials": gm.get_active_initials()? })),
+        )
+    } else if args.scope {
+        output.print(
+            || gm.print_scope(),
+            || Ok(json!({ "scope": gm.get_active_scope()? })),
+        )
+    } else if args.driver {
+        output.print(
+            || gm.print_driver(),
+            || Ok(json!({ "rotation": gm.get_rotation()? })),
+        )
+    } else {
+        output.print(|| gm.print(), || gm.get_mob_status())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use git_mob_rs::test_utils::get_git_mob;
+
+    #[test]
+    fn test_print() {
+        let gm = get_git_mob();
+
+        let authors =
+            "\n\nCo-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>\n";
+
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+
+        assert_eq!(authors, gm.print().unwrap());
+    }
+
+    #[test]
+    fn test_print_initials() {
+        let gm = get_git_mob();
+
+        gm.write_gitmessage(&[]).unwrap();
+
+        assert_eq!("\n", gm.print_initials().unwrap());
+
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+
+        assert_eq!("ab,cd\n", gm.print_initials().unwrap());
+    }
+
+    #[test]
+    fn test_print_scope() {
+        let gm = get_git_mob();
+
+        assert_eq!("", gm.print_scope().unwrap());
+
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+
+        assert_eq!("local\n", gm.print_scope().unwrap());
+    }
+
+    #[test]
+    fn test_print_driver() {
+        let gm = get_git_mob();
+
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+        assert_eq!("", gm.print_driver().unwrap());
+
+        gm.start_timer(chrono::Duration::minutes(10), &[]).unwrap();
+        gm.clock.set("2024-01-01T09:10:00+00:00");
+        assert_eq!("cd\n", gm.print_driver().unwrap());
+    }
+}
@@ -0,0 +1,58 @@
+use clap::Args;
+use git_mob_rs::{clock::Clock, error::Result, file_actions::FileActions, output::Output, GitMob};
+
+#[derive(Args)]
+pub struct SoloArgs {
+    /// Remove the mob set for every repository with "git mob --global"
+    #[arg(short, long)]
+    global: bool,
+}
+
+pub trait Solo {
+    fn solo(&self) -> Result<String>;
+    fn solo_global(&self) -> Result<String>;
+}
+
+impl<T: FileActions, C: Clock> Solo for GitMob<T, C> {
+    fn solo(&self) -> Result<String> {
+        self.write_gitmessage(&[])?;
+        self.get_formatted_gitmessage()
+    }
+
+    fn solo_global(&self) -> Result<String> {
+        self.clear_global_gitmessage()?;
+
+        match self.get_active_scope()? {
+            Some(_) => self.get_formatted_gitmessage(),
+            None => self.get_git_user(),
+        }
+    }
+}
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    args: SoloArgs,
+    output: Output,
+) -> Result<()> {
+    let message = if args.global {
+        gm.solo_global()?
+    } else {
+        gm.solo()?
+    };
+    output.print(|| Ok(format!("{message}\n")), || gm.get_mob_status())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use git_mob_rs::test_utils::get_git_mob;
+
+    #[test]
+    fn test_solo() {
+        let gm = get_git_mob();
+        let actual = gm.solo().unwrap();
+
+        assert_eq!("", gm.get_gitmessage().unwrap());
+        assert_eq!(gm.get_git_user().unwrap(), actual);
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Duration, FixedOffset};
+use clap::Subcommand;
+use clap_complete::ArgValueCandidates;
+use git_mob_rs::{
+    clock::Clock,
+    completions,
+    error::Result,
+    file_actions::FileActions,
+    output::Output,
+    timer::{format_remaining, parse_every, Rotation},
+    GitMob,
+};
+use serde_json::json;
+use std::thread;
+
+#[derive(Subcommand)]
+pub enum TimerCommand {
+    /// Start rotating the driver of the current mob.
+    /// For example: git mob timer start --every 15m --order cd,ab
+    Start {
+        /// How long each driver drives, such as 90s, 10m or 1h, up to 7d
+        #[arg(short, long, default_value = "10m", value_parser = parse_every)]
+        every: Duration,
+        /// The order to drive in, by default the mob's. Can include yourself and groups
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            add = ArgValueCandidates::new(completions::complete_initials)
+        )]
+        order: Vec<String>,
+    },
+    /// Show who is driving and who is next
+    Status,
+    /// Hand over to the next driver now, who then gets a whole turn
+    Next,
+    /// Stop the timer
+    Stop,
+    /// Keep running to announce each rotation in the terminal, until the timer is stopped
+    Watch,
+}
+
+/// Formats who is driving, and who is next in how long
+fn format_rotation(rotation: &Rotation, now: DateTime<FixedOffset>) -> String {
+    format!(
+        "{} is driving, {} is next in {}\n",
+        rotation.driver,
+        rotation.next,
+        format_remaining(rotation.rotates_at - now)
+    )
+}
+
+const NOT_RUNNING: &str = "The timer isn't running, start it with \"git mob timer start\"\n";
+
+pub fn run<T: FileActions, C: Clock>(
+    gm: &GitMob<T, C>,
+    command: Option<TimerCommand>,
+    output: Output,
+) -> Result<()> {
+    match command.unwrap_or(TimerCommand::Status) {
+        TimerCommand::Start { every, order } => {
+            let timer = gm.start_timer(every, &order)?;
+            let now = gm.clock.now();
+            let rotation = timer.rotation(now)?;
+            output.print(
+                || {
+                    Ok(format!(
+                        "Rotating {} every {}\n{}",
+                        timer.order.join(", "),
+                        format_remaining(every),
+                        format_rotation(&rotation, now)
+                    ))
+                },
+                || Ok(json!({ "timer": timer, "rotation": rotation })),
+            )
+        }
+        TimerCommand::Status => {
+            let now = gm.clock.now();
+            let rotation = gm.get_rotation()?;
+            output.print(
+                || {
+                    Ok(match &rotation {
+                        Some(rotation) => format_rotation(rotation, now),
+                        None => String::from(NOT_RUNNING),
+                    })
+                },
+                || Ok(json!({ "rotation": rotation })),
+            )
+        }
+        TimerCommand::Next => {
+            let timer = gm.skip_driver()?;
+            let now = gm.clock.now();
+            let rotation = timer.map(|timer| timer.rotation(now)).transpose()?;
+            output.print(
+                || {
+                    Ok(match &rotation {
+                        Some(rotation) => format_rotation(rotation, now),
+                        None => String::from(NOT_RUNNING),
+                    })
+                },
+                || Ok(json!({ "rotation": rotation })),
+            )
+        }
+        TimerCommand::Stop => {
+            let stopped = gm.stop_timer()?;
+            output.print(
+                || {
+                    Ok(String::from(if stopped {
+                        "The timer has been stopped\n"
+                    } else {
+                        NOT_RUNNING
+                    }))
+                },
+                || Ok(json!({ "stopped": stopped })),
+            )
+        }
+        TimerCommand::Watch => watch(gm, output),
+    }
+}
+
+/// Announces each rotation until the timer is stopped
+///
+/// The timer is read again every second, so "git mob timer next" and "stop" from another
+/// terminal are picked up.
+fn watch<T: FileActions, C: Clock>(gm: &GitMob<T, C>, output: Output) -> Result<()> {
+    let mut turn = None;
+    loop {
+        let Some(timer) = gm.get_timer()? else {
+            return output.print(
+                || {
+                    Ok(String::from(match turn {
+                        Some(_) => "The timer has been stopped\n",
+                        None => NOT_RUNNING,
+                    }))
+                },
+                || Ok(json!({ "rotation": null })),
+            );
+        };
+
+        let now = gm.clock.now();
+        let rotation = timer.rotation(now)?;
+        if turn != Some(rotation.turn) {
+            let announcement = format_rotation(&rotation, now);
+            output.print(
+                || {
+                    Ok(match turn {
+                        // ring the terminal bell to get the mob's attention
+                        Some(_) => format!("\x07Rotate! {announcement}"),
+                        None => announcement,
+                    })
+                },
+                || Ok(json!({ "rotation": rotation })),
+            )?;
+            turn = Some(rotation.turn);
+        }
+
+        let remaining = (rotation.rotates_at - now).to_std().unwrap_or_default();
+        thread::sleep(remaining.min(std::time::Duration::from_secs(1)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_rotation() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T09:05:30+00:00").unwrap();
+        let rotation = Rotation {
+            driver: String::from("ab"),
+            next: String::from("cd"),
+            turn: 0,
+            rotates_at: DateTime::parse_from_rfc3339("2024-01-01T09:10:00+00:00").unwrap(),
+        };
+
+        assert_eq!(
+            "ab is driving, cd is next in 4m 30s\n",
+            format_rotation(&rotation, now)
+        );
+    }
+}
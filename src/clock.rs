@@ -0,0 +1,13 @@
+use chrono::{DateTime, FixedOffset, Local};
+
+pub trait Clock {
+    fn now(&self) -> DateTime<FixedOffset>;
+}
+
+pub struct SystemClock();
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        Local::now().fixed_offset()
+    }
+}
@@ -0,0 +1,215 @@
+use crate::clock::Clock;
+use crate::file_actions::FileActions;
+use crate::links::LINKS;
+use crate::{Author, GitMob};
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+use clap_complete::CompletionCandidate;
+use std::io::{self, Write};
+use std::iter;
+
+/// The environment variable the shells set to ask a binary for completions
+pub(crate) const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Completes co-author initials, with each person's name and email as the description, and
+/// group names, with their members
+pub fn complete_initials() -> Vec<CompletionCandidate> {
+    GitMob::default().get_initials_candidates()
+}
+
+/// Completes the initials in the user's own coauthors file, the only ones that can be edited or
+/// deleted
+pub fn complete_user_initials() -> Vec<CompletionCandidate> {
+    GitMob::default().get_user_initials_candidates()
+}
+
+/// Completes the names of the groups in the user's coauthors file, with their members
+pub fn complete_groups() -> Vec<CompletionCandidate> {
+    GitMob::default().get_user_group_candidates()
+}
+
+fn coauthor_candidate(initials: String, author: Author) -> CompletionCandidate {
+    let name = author.name;
+    let email = author.email;
+    CompletionCandidate::new(initials).help(Some(format!("{name} <{email}>").into()))
+}
+
+fn group_candidate(name: String, members: Vec<String>) -> CompletionCandidate {
+    CompletionCandidate::new(name).help(Some(members.join(", ").into()))
+}
+
+// a broken roster shouldn't make the shell print errors while typing, so these complete nothing
+// instead
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    fn get_initials_candidates(&self) -> Vec<CompletionCandidate> {
+        let coauthors = self.get_all_coauthors().unwrap_or_default();
+        let groups = self.get_all_groups().unwrap_or_default();
+
+        let groups: Vec<CompletionCandidate> = groups
+            .into_iter()
+            .filter(|(name, _)| !coauthors.contains_key(name))
+            .map(|(name, members)| group_candidate(name, members))
+            .collect();
+        coauthors
+            .into_iter()
+            .map(|(initials, author)| coauthor_candidate(initials, author))
+            .chain(groups)
+            .collect()
+    }
+
+    fn get_user_initials_candidates(&self) -> Vec<CompletionCandidate> {
+        self.get_user_coauthors()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(initials, author)| coauthor_candidate(initials, author))
+            .collect()
+    }
+
+    fn get_user_group_candidates(&self) -> Vec<CompletionCandidate> {
+        self.get_user_groups()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, members)| group_candidate(name, members))
+            .collect()
+    }
+}
+
+/// Writes the completion script for `shell` ("bash", "zsh" or "fish") for "git-mob" and every
+/// link to it
+///
+/// They all ask "git-mob" for completions, so the links complete even before they're installed.
+/// This also hooks into git's own completion, so "git mob <TAB>" completes like "git-mob <TAB>".
+/// Fish's git completion already does this for every "git-*" command.
+pub fn write_completions(shell: &str, buf: &mut dyn Write) -> io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        "bash" => &Bash,
+        "zsh" => &Zsh,
+        "fish" => &Fish,
+        _ => {
+            return Err(io::Error::other(format!(
+                "\"{shell}\" should be \"bash\", \"zsh\" or \"fish\""
+            )))
+        }
+    };
+
+    let binaries = iter::once("git-mob").chain(LINKS.iter().map(|(link, _)| *link));
+    for bin in binaries {
+        completer.write_registration(COMPLETE_VAR, bin, bin, "git-mob", buf)?;
+
+        let name = bin.replace('-', "_");
+        let command = bin.trim_start_matches("git-");
+        let git_command = command.replace('-', "_");
+        match shell {
+            // git's completion calls "_git_<command>" with the whole "git ..." command line
+            "bash" => writeln!(
+                buf,
+                r#"_git_{git_command}() {{
+    local cmd_idx=${{__git_cmd_idx:-1}}
+    local COMP_WORDS=({bin} "${{COMP_WORDS[@]:cmd_idx+1}}")
+    local COMP_CWORD=$((COMP_CWORD - cmd_idx))
+    _clap_complete_{name} {bin} "${{COMP_WORDS[COMP_CWORD]}}"
+}}"#
+            )?,
+            // zsh's git completion calls "_git-<command>" with the words after "git"
+            "zsh" => writeln!(
+                buf,
+                r#"
+_git-{command}() {{
+    words[1]={bin}
+    _clap_dynamic_completer_{name}
+}}"#
+            )?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+    use serde_json::json;
+
+    fn describe(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|candidate| {
+                let help = candidate.get_help().map(ToString::to_string);
+                format!(
+                    "{} {}",
+                    candidate.get_value().to_string_lossy(),
+                    help.unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_initials_candidates() {
+        let gm = get_git_mob();
+        gm.file_actions
+            .write(
+                &gm.get_coauthors_path().unwrap(),
+                &json!({
+                    "coauthors": {
+                        "ab": { "name": "A B", "email": "ab@example.com" },
+                        "cd": { "name": "C D", "email": "cd@example.com" }
+                    },
+                    "groups": { "core": ["ab", "cd"] }
+                })
+                .to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                "ab A B <ab@example.com>",
+                "cd C D <cd@example.com>",
+                "core ab, cd"
+            ],
+            describe(gm.get_initials_candidates())
+        );
+        // editing and deleting a co-author takes initials from the user's own file, not groups
+        assert_eq!(
+            vec!["ab A B <ab@example.com>", "cd C D <cd@example.com>"],
+            describe(gm.get_user_initials_candidates())
+        );
+        assert_eq!(
+            vec!["core ab, cd"],
+            describe(gm.get_user_group_candidates())
+        );
+
+        // a broken roster completes nothing rather than failing
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), "{")
+            .unwrap();
+        assert!(gm.get_initials_candidates().is_empty());
+        assert!(gm.get_user_initials_candidates().is_empty());
+    }
+
+    #[test]
+    fn test_write_completions() {
+        let complete = |shell: &str| {
+            let mut buf = vec![];
+            write_completions(shell, &mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        let bash = complete("bash");
+        assert!(bash.contains("_git_mob() {"));
+        assert!(bash.contains("_git_edit_coauthor() {"));
+        assert!(bash.contains("_clap_complete_git_solo git-solo"));
+
+        let zsh = complete("zsh");
+        assert!(zsh.contains("_git-mob() {"));
+        assert!(zsh.contains("_git-mob-print() {"));
+
+        // fish's git completion already handles "git mob"
+        let fish = complete("fish");
+        assert!(fish.contains("git-coauthors"));
+        assert!(!fish.contains("_git"));
+
+        let mut buf = vec![];
+        assert!(write_completions("powershell", &mut buf).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::{Author, GitMob};
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Someone who authored or co-authored commits in a release, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Contributor {
+    /// Their initials, if they're in the coauthors file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initials: Option<String>,
+    /// Their name and email from the coauthors file, or else from their latest commit
+    #[serde(flatten)]
+    pub author: Author,
+    /// The ids of the commits they authored or co-authored, newest first
+    pub commits: Vec<String>,
+}
+
+/// Returns a name without case, spaces or punctuation, so "A B" and "a.b" are the same
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns everyone who authored or co-authored the commits, most commits first
+///
+/// People are matched to the roster by email, or else by name ignoring case, spaces and
+/// punctuation, and get its name and email. Everyone else is told apart by their email.
+pub fn collect_credits(
+    commits: &[CommitInfo],
+    roster: &LinkedHashMap<String, Author>,
+) -> Vec<Contributor> {
+    let find_in_roster = |person: &Author| {
+        roster
+            .iter()
+            .find(|(_, author)| author.email.eq_ignore_ascii_case(&person.email))
+            .or_else(|| {
+                let name = normalize_name(&person.name);
+                roster
+                    .iter()
+                    .find(|(_, author)| !name.is_empty() && normalize_name(&author.name) == name)
+            })
+    };
+
+    let mut contributors: Vec<Contributor> = vec![];
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for commit in commits {
+        let mut people = vec![commit.author.clone()];
+        people.extend(commit.coauthors());
+
+        for person in people {
+            let (key, contributor) = match find_in_roster(&person) {
+                Some((initials, author)) => (
+                    format!("initials:{initials}"),
+                    Contributor {
+                        initials: Some(initials.to_string()),
+                        author: author.clone(),
+                        commits: vec![],
+                    },
+                ),
+                None => (
+                    person.email.to_lowercase(),
+                    Contributor {
+                        initials: None,
+                        author: person,
+                        commits: vec![],
+                    },
+                ),
+            };
+            let index = *by_key.entry(key).or_insert_with(|| {
+                contributors.push(contributor);
+                contributors.len() - 1
+            });
+
+            // co-authoring your own commit doesn't count twice
+            let commits = &mut contributors[index].commits;
+            if commits.last() != Some(&commit.id) {
+                commits.push(commit.id.to_string());
+            }
+        }
+    }
+
+    contributors.sort_by(|a, b| {
+        b.commits.len().cmp(&a.commits.len()).then_with(|| {
+            a.author
+                .name
+                .to_lowercase()
+                .cmp(&b.author.name.to_lowercase())
+        })
+    });
+    contributors
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the contributors to a range such as "v1.0..v1.1", see `collect_credits`
+    pub fn get_credits(&self, range: &str) -> Result<Vec<Contributor>> {
+        let commits = self.get_commits(range, None, None)?;
+        Ok(collect_credits(&commits, &self.get_all_coauthors()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit, get_git_mob};
+
+    #[test]
+    fn test_collect_credits() {
+        let gm = get_git_mob();
+        let commits = [
+            commit(
+                "3",
+                author("a.b", "a.b@users.example.com"),
+                "Fix\n\nCo-authored-by: E F <ef@example.com>\n",
+            ),
+            commit(
+                "2",
+                author("E F", "EF@example.com"),
+                "Add\n\nCo-authored-by: C D <CD@example.com>\nCo-authored-by: E F <ef@example.com>\n",
+            ),
+            commit("1", author("A B", "ab@example.com"), "Start\n"),
+        ];
+
+        assert_eq!(
+            vec![
+                Contributor {
+                    initials: Some(String::from("ab")),
+                    author: author("A B", "ab@example.com"),
+                    commits: vec![String::from("3"), String::from("1")],
+                },
+                Contributor {
+                    initials: None,
+                    author: author("E F", "ef@example.com"),
+                    commits: vec![String::from("3"), String::from("2")],
+                },
+                Contributor {
+                    initials: Some(String::from("cd")),
+                    author: author("C D", "cd@example.com"),
+                    commits: vec![String::from("2")],
+                },
+            ],
+            collect_credits(&commits, &gm.get_all_coauthors().unwrap())
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::template::format_gitmessage;
+use crate::{find_rosters, git_config, to_config_path, GitMob, MobScope, GITMESSAGE};
+use dirs::{config_dir, home_dir};
+use gix_config::Source;
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Mobbing works, but maybe not the way it's expected to
+    Warning,
+    /// Mobbing doesn't work
+    Error,
+    /// There was a problem, and "git mob doctor --fix" fixed it
+    Fixed,
+}
+
+/// The result of one of the doctor's checks
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Check {
+    /// What was checked, such as "identity"
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: String) -> Self {
+        Check {
+            name,
+            status: CheckStatus::Ok,
+            message,
+            hint: None,
+        }
+    }
+
+    fn problem(name: &'static str, status: CheckStatus, message: String, hint: &str) -> Self {
+        Check {
+            name,
+            status,
+            message,
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    /// A problem that "git mob doctor --fix" can fix, which is fixed with `fix` if `fixing`
+    fn fixable(
+        name: &'static str,
+        message: String,
+        fixing: bool,
+        fix: impl FnOnce() -> Result<String>,
+    ) -> Result<Self> {
+        if !fixing {
+            return Ok(Check::problem(
+                name,
+                CheckStatus::Error,
+                message,
+                "Run \"git mob doctor --fix\" to fix it",
+            ));
+        }
+        Ok(Check {
+            name,
+            status: CheckStatus::Fixed,
+            message: format!("{message}, {}", fix()?),
+            hint: None,
+        })
+    }
+}
+
+/// Describes where a config value comes from, such as the path of its file
+fn describe_source(source: Source, path: Option<&Path>) -> String {
+    match (source, path) {
+        (_, Some(path)) => std::path::absolute(path)
+            .unwrap_or(path.to_path_buf())
+            .display()
+            .to_string(),
+        (Source::Env | Source::EnvOverride, None) => String::from("the environment"),
+        (Source::Cli, None) => String::from("the command line"),
+        (source, None) => format!("the {source:?} config"),
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Checks the repository and the user's setup for the usual reasons mobbing doesn't work,
+    /// fixing what can be fixed if `fix` is set
+    pub fn doctor(&self, fix: bool) -> Result<Vec<Check>> {
+        self.get_repo()?;
+
+        let mut checks = vec![self.check_identity()?, self.check_roster()];
+
+        // the initials are fixed first, since the gitmessage is regenerated from them
+        match self.get_active_scope()? {
+            Some(scope) => {
+                checks.push(self.check_initials(scope, fix)?);
+                checks.push(self.check_gitmessage(scope, fix)?);
+                checks.push(self.check_template(scope, fix)?);
+            }
+            None => checks.push(Check::ok(
+                "mob",
+                String::from("No mob has been set, run \"git mob <initials>\" to start one"),
+            )),
+        }
+        Ok(checks)
+    }
+
+    fn check_identity(&self) -> Result<Check> {
+        let name = self.get_git_config_value("user.name")?;
+        let email = self.get_git_config_value("user.email")?;
+        Ok(match (name, email) {
+            (Some(name), Some(email)) => Check::ok("identity", format!("{name} <{email}>")),
+            (name, _) => Check::problem(
+                "identity",
+                CheckStatus::Error,
+                format!(
+                    "Your git config \"{}\" is missing, so your own commits can't be attributed",
+                    if name.is_none() { "user.name" } else { "user.email" }
+                ),
+                "Run \"git config --global user.name \\\"Your Name\\\"\" and \"git config --global user.email you@example.com\"",
+            ),
+        })
+    }
+
+    fn check_roster(&self) -> Check {
+        let path = match self.get_coauthors_path() {
+            Ok(path) => path,
+            Err(error) => {
+                return Check::problem(
+                    "coauthors",
+                    CheckStatus::Error,
+                    error.to_string(),
+                    "Set GITMOB_COAUTHORS_PATH to your coauthors file",
+                )
+            }
+        };
+        if let Err(error) = self.get_all_coauthors() {
+            return Check::problem(
+                "coauthors",
+                CheckStatus::Error,
+                error.to_string(),
+                "Run \"git coauthors lint\" to find the problems",
+            );
+        }
+
+        if env::var("GITMOB_COAUTHORS_PATH").is_ok() {
+            return Check::ok(
+                "coauthors",
+                format!("Using {} from GITMOB_COAUTHORS_PATH", path.display()),
+            );
+        }
+
+        // only the first one that exists is used, the others are silently ignored
+        let rosters: Vec<PathBuf> = vec![
+            config_dir().map(|dir| find_rosters(&dir, "git-coauthors")),
+            home_dir().map(|dir| find_rosters(&dir, ".git-coauthors")),
+        ]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect();
+        match rosters.as_slice() {
+            [] => Check::problem(
+                "coauthors",
+                CheckStatus::Warning,
+                format!("{} doesn't exist yet", path.display()),
+                "Run \"git mob coauthor add\" or \"git mob coauthor open\" to add co-authors",
+            ),
+            [_] => Check::ok("coauthors", format!("Using {}", path.display())),
+            [_, ignored @ ..] => Check::problem(
+                "coauthors",
+                CheckStatus::Warning,
+                format!(
+                    "Using {}, so {} {} ignored",
+                    path.display(),
+                    ignored
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    if ignored.len() == 1 { "is" } else { "are" }
+                ),
+                "Move the co-authors into one file and remove the others",
+            ),
+        }
+    }
+
+    /// Checks that the mob's initials are all in the coauthors file
+    fn check_initials(&self, scope: MobScope, fix: bool) -> Result<Check> {
+        let initials = self.get_active_initials()?;
+        let Ok(coauthors) = self.get_all_coauthors() else {
+            return Ok(Check::problem(
+                "mob",
+                CheckStatus::Warning,
+                format!("The {scope} mob can't be checked until the coauthors file is fixed"),
+                "Run \"git coauthors lint\" to find the problems",
+            ));
+        };
+
+        let (known, unknown): (Vec<String>, Vec<String>) = initials
+            .into_iter()
+            .partition(|initial| coauthors.contains_key(initial));
+        if unknown.is_empty() {
+            return Ok(Check::ok(
+                "mob",
+                match known.as_slice() {
+                    [] => format!("Solo, set in the {scope} mob"),
+                    known => format!("The {scope} mob is {}", known.join(", ")),
+                },
+            ));
+        }
+
+        Check::fixable(
+            "mob",
+            format!(
+                "{} of the {scope} mob {} not in the coauthors file",
+                unknown.join(", "),
+                if unknown.len() == 1 { "is" } else { "are" }
+            ),
+            fix,
+            || {
+                let trailer = self.get_active_trailer_format()?;
+                self.write_mob_files(scope, &known, &trailer)?;
+                Ok(String::from("removed them from the mob"))
+            },
+        )
+    }
+
+    /// Checks that the gitmessage file has the trailers of the mob's initials
+    fn check_gitmessage(&self, scope: MobScope, fix: bool) -> Result<Check> {
+        let path = self.get_scope_path(scope, GITMESSAGE)?;
+        let absolute_path = std::path::absolute(&path).unwrap_or(path.clone());
+        let expected = match self.get_coauthor_trailers(&self.get_active_initials()?) {
+            Ok(trailers) => format_gitmessage(
+                &self.get_previous_template_content(scope)?,
+                &trailers,
+                &self.get_comment_prefix()?,
+            ),
+            Err(_) => {
+                return Ok(Check::problem(
+                    "gitmessage",
+                    CheckStatus::Warning,
+                    format!(
+                        "{} can't be checked until the mob is fixed",
+                        absolute_path.display()
+                    ),
+                    "Run \"git mob <initials>\" to set the mob again",
+                ))
+            }
+        };
+
+        let message = match self.file_actions.read(&path) {
+            Ok(gitmessage) if gitmessage == expected => {
+                return Ok(Check::ok(
+                    "gitmessage",
+                    format!("{} matches the mob", absolute_path.display()),
+                ))
+            }
+            Ok(_) => format!("{} doesn't match the mob", absolute_path.display()),
+            Err(_) => format!("{} is missing", absolute_path.display()),
+        };
+        Check::fixable("gitmessage", message, fix, || {
+            self.file_actions
+                .write(&path, &expected)
+                .map_err(GitMobError::Io)?;
+            Ok(String::from("regenerated it from the mob's initials"))
+        })
+    }
+
+    /// Checks that the `commit.template` git uses is the mob's, and not one set somewhere that
+    /// takes precedence
+    fn check_template(&self, scope: MobScope, fix: bool) -> Result<Check> {
+        let repo = self.get_repo()?;
+        let gitmessage_path = self.get_scope_path(scope, GITMESSAGE)?;
+        let expected = match scope {
+            MobScope::Local => self.get_template_config_value(&repo),
+            MobScope::Global => to_config_path(&gitmessage_path),
+        };
+
+        // the sections are in order of precedence, so the last template is the one git uses
+        let config = repo.config_snapshot();
+        let template = config
+            .plumbing()
+            .sections_by_name("commit")
+            .into_iter()
+            .flatten()
+            .filter(|section| section.header().subsection_name().is_none())
+            .filter_map(|section| {
+                let value = section.value("template")?;
+                Some((value.to_string(), section.meta().clone()))
+            })
+            .last();
+
+        let (template, meta) = match template {
+            Some((template, _))
+                if template == expected || template == to_config_path(&gitmessage_path) =>
+            {
+                return Ok(Check::ok(
+                    "template",
+                    format!("commit.template is the {scope} mob's {template}"),
+                ));
+            }
+            Some(template) => template,
+            None => {
+                return Check::fixable(
+                    "template",
+                    String::from("commit.template is not set"),
+                    fix,
+                    || self.fix_template(scope),
+                )
+            }
+        };
+
+        let source = describe_source(meta.source, meta.path.as_deref());
+        let message = format!(
+            "commit.template is \"{template}\" from {source}, instead of the {scope} mob's {expected}"
+        );
+        // the mob's own config, or for a local mob, a template left over from a mob kept per
+        // worktree
+        let own_configs = match scope {
+            MobScope::Local => vec![
+                repo.common_dir().join("config"),
+                repo.path().join("config.worktree"),
+            ],
+            MobScope::Global => vec![git_config::global_path()?],
+        };
+        let is_own_config = meta.path.as_deref().is_some_and(|path| {
+            own_configs
+                .iter()
+                .any(|own_config| to_config_path(own_config) == to_config_path(path))
+        });
+
+        if is_own_config {
+            Check::fixable("template", message, fix, || self.fix_template(scope))
+        } else if scope == MobScope::Global
+            && matches!(meta.source, Source::Local | Source::Worktree)
+        {
+            Ok(Check::problem(
+                "template",
+                CheckStatus::Warning,
+                message,
+                "Run \"git mob <initials>\" to set the mob in this repository, which keeps its template",
+            ))
+        } else {
+            Ok(Check::problem(
+                "template",
+                CheckStatus::Error,
+                message,
+                &format!("Remove commit.template from {source}"),
+            ))
+        }
+    }
+
+    fn fix_template(&self, scope: MobScope) -> Result<String> {
+        match scope {
+            MobScope::Local => self.set_git_template()?,
+            MobScope::Global => self.set_git_template_config(
+                &git_config::global_path()?,
+                Source::User,
+                &to_config_path(&self.get_scope_path(MobScope::Global, GITMESSAGE)?),
+            )?,
+        }
+        Ok(String::from("pointed it at the mob's gitmessage"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+    use crate::GITINITIALS;
+
+    fn get_check(checks: &[Check], name: &str) -> CheckStatus {
+        checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn test_doctor_regenerates_gitmessage() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+        let checks = gm.doctor(false).unwrap();
+        assert_eq!(CheckStatus::Ok, get_check(&checks, "mob"));
+        assert_eq!(CheckStatus::Ok, get_check(&checks, "gitmessage"));
+
+        let gitmessage_path = gm.get_gitmessage_path().unwrap();
+        gm.file_actions.write(&gitmessage_path, "").unwrap();
+        let checks = gm.doctor(false).unwrap();
+        assert_eq!(CheckStatus::Error, get_check(&checks, "gitmessage"));
+        assert_eq!("", gm.get_gitmessage().unwrap());
+
+        let checks = gm.doctor(true).unwrap();
+        assert_eq!(CheckStatus::Fixed, get_check(&checks, "gitmessage"));
+        assert_eq!(
+            "\n\nCo-authored-by: A B <ab@example.com>",
+            gm.get_gitmessage().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_doctor_removes_unknown_initials() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+        gm.file_actions
+            .write(&gm.get_gitinitials_path().unwrap(), "ab,ef\n")
+            .unwrap();
+
+        let checks = gm.doctor(false).unwrap();
+        assert_eq!(CheckStatus::Error, get_check(&checks, "mob"));
+        assert_eq!(CheckStatus::Warning, get_check(&checks, "gitmessage"));
+
+        let checks = gm.doctor(true).unwrap();
+        assert_eq!(CheckStatus::Fixed, get_check(&checks, "mob"));
+        assert_eq!(CheckStatus::Ok, get_check(&checks, "gitmessage"));
+        assert_eq!(
+            "ab\n",
+            gm.file_actions
+                .read(&gm.get_scope_path(MobScope::Local, GITINITIALS).unwrap())
+                .unwrap()
+        );
+    }
+}
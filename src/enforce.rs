@@ -0,0 +1,336 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::hooks::SKIPPED_PREFIXES;
+use crate::message::{CommitMessage, RequiredTrailer};
+use crate::rewrite::ancestors;
+use crate::GitMob;
+use gix::{ObjectId, Repository};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A commit being pushed without some of the mob's trailers
+#[derive(Debug, PartialEq, Eq)]
+pub struct Offender {
+    pub id: String,
+    pub subject: String,
+    pub missing: Vec<String>,
+}
+
+/// Returns whether a commit is exempt, such as a `fixup!` that will be squashed away
+fn is_exempt(message: &str) -> bool {
+    let subject = message.trim_start().lines().next().unwrap_or_default();
+    SKIPPED_PREFIXES
+        .iter()
+        .any(|prefix| subject.starts_with(prefix))
+}
+
+const BYPASS: &str = "or use --no-verify to skip this check";
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the trailers of the active mob, resolved like its .gitmessage template
+    fn get_required_trailers(&self) -> Result<Vec<RequiredTrailer>> {
+        if let Some(warning) = self.expire_stale_mob()? {
+            eprintln!("Warning: {warning}");
+        }
+        self.get_mob_trailers()
+    }
+
+    /// Returns the trailers of the active mob, with who each of them credits
+    pub(crate) fn get_mob_trailers(&self) -> Result<Vec<RequiredTrailer>> {
+        let initials = self.get_active_initials()?;
+        let trailers = self.get_coauthor_trailers(&initials)?;
+        let coauthors = self.get_all_coauthors()?;
+        Ok(initials
+            .iter()
+            .zip(trailers)
+            .map(|(initials, trailer)| RequiredTrailer {
+                email: coauthors[initials].email.clone(),
+                trailer,
+            })
+            .collect())
+    }
+
+    /// Returns an error if the commit message file given to the `commit-msg` hook is missing
+    /// any of the active mob's trailers
+    ///
+    /// Like a pushed commit, it never has to credit whoever is committing it.
+    pub fn check_commit_msg(&self, message_path: &Path) -> Result<()> {
+        let required = self.get_required_trailers()?;
+        if required.is_empty() {
+            return Ok(());
+        }
+
+        let message = self
+            .file_actions
+            .read(message_path)
+            .map_err(GitMobError::Io)?;
+        if is_exempt(&message) {
+            return Ok(());
+        }
+
+        let comment = self.get_comment_prefix()?;
+        let author_email = self.get_git_author()?.email;
+        let missing = CommitMessage::parse(&message, Some(&comment))
+            .missing_required(&required, Some(&author_email));
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let mut error = String::from("The commit message is missing the mob's trailers:\n");
+        for required in missing {
+            error.push_str(&format!("    {}\n", required.trailer));
+        }
+        error.push_str(&format!("Add them, {BYPASS}"));
+        Err(GitMobError::MissingTrailers(error))
+    }
+
+    /// Returns an error listing the commits a push would send to `remote` without the active
+    /// mob's trailers
+    ///
+    /// `updates` is what git gives the `pre-push` hook on stdin, a line with
+    /// "<local ref> <local sha> <remote ref> <remote sha>" for each ref being pushed.
+    pub fn check_pre_push(&self, remote: &str, updates: &str) -> Result<()> {
+        let offenders = self.get_push_offenders(remote, updates)?;
+        if offenders.is_empty() {
+            return Ok(());
+        }
+
+        let mut error = format!(
+            "{} being pushed {} missing the mob's trailers:\n",
+            match offenders.len() {
+                1 => String::from("1 commit"),
+                count => format!("{count} commits"),
+            },
+            if offenders.len() == 1 { "is" } else { "are" }
+        );
+        for offender in &offenders {
+            error.push_str(&format!("{} {}\n", offender.id, offender.subject));
+            for trailer in &offender.missing {
+                error.push_str(&format!("    {trailer}\n"));
+            }
+        }
+        error.push_str(&format!(
+            "Add them with \"git mob --rewrite <range>\", {BYPASS}"
+        ));
+        Err(GitMobError::MissingTrailers(error))
+    }
+
+    /// Returns the commits a push would send to `remote` without the active mob's trailers,
+    /// oldest first
+    ///
+    /// Merges and `fixup!` commits are left out, and a commit never has to credit its own author.
+    pub fn get_push_offenders(&self, remote: &str, updates: &str) -> Result<Vec<Offender>> {
+        let required = self.get_required_trailers()?;
+        if required.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let repo = self.get_repo()?;
+        let git_error = |error: &dyn std::fmt::Display| {
+            GitMobError::Io(format!("Unable to read the commits to push: {error}"))
+        };
+
+        let mut offenders = vec![];
+        for id in outgoing_commits(&repo, remote, updates).map_err(|error| git_error(&error))? {
+            let commit = repo.find_commit(id).map_err(|error| git_error(&error))?;
+            let message = commit.message_raw_sloppy().to_string();
+            if commit.parent_ids().count() > 1 || is_exempt(&message) {
+                continue;
+            }
+
+            let author = commit.author().map_err(|error| git_error(&error))?;
+            let author_email = author.email.to_string();
+            let missing = CommitMessage::parse(&message, None)
+                .missing_required(&required, Some(&author_email));
+            if !missing.is_empty() {
+                offenders.push(Offender {
+                    id: id.to_hex_with_len(7).to_string(),
+                    subject: message.lines().next().unwrap_or_default().to_string(),
+                    missing: missing
+                        .into_iter()
+                        .map(|required| required.trailer.clone())
+                        .collect(),
+                });
+            }
+        }
+
+        offenders.reverse();
+        Ok(offenders)
+    }
+}
+
+/// Returns the commits the pushed refs have that the remote doesn't, newest first
+///
+/// What the remote has is taken from the commits it's told to replace and from its
+/// remote-tracking branches, so a new branch only lists the commits that aren't pushed yet.
+fn outgoing_commits(
+    repo: &Repository,
+    remote: &str,
+    updates: &str,
+) -> std::result::Result<Vec<ObjectId>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut tips = vec![];
+    let mut pushed = vec![];
+    for line in updates.lines() {
+        let [_, local, _, remote_id] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            continue;
+        };
+        let local = ObjectId::from_hex(local.as_bytes())?;
+        let remote_id = ObjectId::from_hex(remote_id.as_bytes())?;
+        // deleting a remote ref pushes nothing
+        if !local.is_null() {
+            tips.push(local);
+        }
+        // the remote can have commits that were never fetched
+        if !remote_id.is_null() && repo.find_commit(remote_id).is_ok() {
+            pushed.push(remote_id);
+        }
+    }
+    if tips.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let references = repo.references()?;
+    for reference in references.prefixed(format!("refs/remotes/{remote}/"))? {
+        if let Ok(id) = reference?.peel_to_id_in_place() {
+            pushed.push(id.detach());
+        }
+    }
+
+    let mut hidden = HashSet::new();
+    for id in pushed {
+        hidden.extend(ancestors(repo, id)?);
+    }
+    repo.rev_walk(tips)
+        .selected(move |id| !hidden.contains(id))?
+        .map(|info| info.map(|info| info.id).map_err(Into::into))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, get_git_mob};
+
+    const TRAILERS: &str =
+        "Co-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>\n";
+
+    fn mob() -> Vec<String> {
+        vec![String::from("ab"), String::from("cd")]
+    }
+
+    #[test]
+    fn test_check_commit_msg() {
+        let gm = get_git_mob();
+        let message_path = Path::new("COMMIT_EDITMSG");
+
+        gm.write_gitmessage(&[]).unwrap();
+        gm.file_actions
+            .write(message_path, "Add feature\n")
+            .unwrap();
+        gm.check_commit_msg(message_path).unwrap();
+
+        gm.write_gitmessage(&mob()).unwrap();
+        let error = gm.check_commit_msg(message_path).unwrap_err();
+        assert_eq!(10, error.exit_code());
+        assert!(error
+            .to_string()
+            .contains("    Co-authored-by: C D <cd@example.com>\n"));
+
+        // git's comments don't count
+        gm.file_actions
+            .write(
+                message_path,
+                &format!("Add feature\n\n# {}", TRAILERS.replace('\n', "\n# ")),
+            )
+            .unwrap();
+        assert!(gm.check_commit_msg(message_path).is_err());
+
+        gm.file_actions
+            .write(
+                message_path,
+                &format!("Add feature\n\n{}", TRAILERS.to_lowercase()),
+            )
+            .unwrap();
+        gm.check_commit_msg(message_path).unwrap();
+
+        // whatever their name is spelled like
+        gm.file_actions
+            .write(
+                message_path,
+                "Add feature\n\nCo-authored-by: Aye Bee <ab@example.com>\nCo-authored-by: Cee Dee <CD@example.com>\n",
+            )
+            .unwrap();
+        gm.check_commit_msg(message_path).unwrap();
+
+        gm.file_actions
+            .write(message_path, "fixup! Add feature\n")
+            .unwrap();
+        gm.check_commit_msg(message_path).unwrap();
+    }
+
+    #[test]
+    fn test_check_commit_msg_committer() {
+        let gm = get_git_mob();
+        let message_path = Path::new("COMMIT_EDITMSG");
+        let mut coauthors = gm.get_all_coauthors().unwrap();
+        coauthors.insert(
+            String::from("me"),
+            author("Me", &gm.get_git_author().unwrap().email),
+        );
+        gm.write_coauthors(coauthors).unwrap();
+        gm.write_gitmessage(&[String::from("me"), String::from("cd")])
+            .unwrap();
+
+        // committing yourself, you don't credit yourself
+        gm.file_actions
+            .write(
+                message_path,
+                "Add feature\n\nCo-authored-by: C D <cd@example.com>\n",
+            )
+            .unwrap();
+        gm.check_commit_msg(message_path).unwrap();
+
+        gm.file_actions
+            .write(message_path, "Add feature\n")
+            .unwrap();
+        let error = gm.check_commit_msg(message_path).unwrap_err().to_string();
+        assert!(error.contains("    Co-authored-by: C D <cd@example.com>\n"));
+        assert!(!error.contains("Co-authored-by: Me"));
+    }
+
+    #[test]
+    fn test_get_push_offenders() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&mob()).unwrap();
+        let repo = gm.get_repo().unwrap();
+        let head = repo.head_commit().unwrap();
+        let parent = head.parent_ids().next().unwrap().detach();
+        let zero = ObjectId::null(gix::hash::Kind::Sha1);
+        let update = |local: ObjectId, remote: ObjectId| {
+            format!("refs/heads/main {local} refs/heads/main {remote}\n")
+        };
+
+        let offenders = gm
+            .get_push_offenders("nowhere", &update(head.id, parent))
+            .unwrap();
+        assert_eq!(1, offenders.len());
+        assert_eq!(head.id.to_hex_with_len(7).to_string(), offenders[0].id);
+        assert_eq!(2, offenders[0].missing.len());
+
+        // nothing new to push, and deleting a branch pushes nothing
+        assert!(gm
+            .get_push_offenders("nowhere", &update(head.id, head.id))
+            .unwrap()
+            .is_empty());
+        assert!(gm
+            .get_push_offenders("nowhere", &update(zero, head.id))
+            .unwrap()
+            .is_empty());
+
+        gm.write_gitmessage(&[]).unwrap();
+        assert!(gm
+            .get_push_offenders("nowhere", &update(head.id, parent))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::process::exit;
+
+pub type Result<T> = std::result::Result<T, GitMobError>;
+
+/// Every failure a `GitMob` method can return
+///
+/// Each variant has its own exit code so scripts can branch on the type of failure.
+#[derive(Debug)]
+pub enum GitMobError {
+    /// The current directory is not inside a git repository (exit code 3)
+    NotInRepo,
+    /// Initials that are not in the coauthors file (exit code 4)
+    UnknownInitials {
+        initials: String,
+        coauthors_path: PathBuf,
+    },
+    /// A group that is not in the coauthors file (exit code 4, like unknown initials)
+    UnknownGroup {
+        name: String,
+        coauthors_path: PathBuf,
+    },
+    /// The coauthors file could not be parsed or written (exit code 5)
+    BadRoster { path: PathBuf, message: String },
+    /// A file could not be read or written (exit code 6)
+    Io(String),
+    /// The git config could not be read or written (exit code 7)
+    GitConfig(String),
+    /// A revision or range that doesn't exist or isn't supported (exit code 8)
+    BadRevision(String),
+    /// "git mob doctor" found problems that are not fixed (exit code 9)
+    Unhealthy(String),
+    /// A commit is missing the mob's trailers, found by the `commit-msg` or `pre-push` hook
+    /// (exit code 10)
+    MissingTrailers(String),
+}
+
+impl GitMobError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GitMobError::NotInRepo => 3,
+            GitMobError::UnknownInitials { .. } | GitMobError::UnknownGroup { .. } => 4,
+            GitMobError::BadRoster { .. } => 5,
+            GitMobError::Io(_) => 6,
+            GitMobError::GitConfig(_) => 7,
+            GitMobError::BadRevision(_) => 8,
+            GitMobError::Unhealthy(_) => 9,
+            GitMobError::MissingTrailers(_) => 10,
+        }
+    }
+
+    /// Prints the error to stderr and exits with its exit code
+    pub fn exit(&self) -> ! {
+        eprintln!("{self}");
+        exit(self.exit_code());
+    }
+}
+
+impl fmt::Display for GitMobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitMobError::NotInRepo => write!(f, "Not in a git repository"),
+            GitMobError::UnknownInitials {
+                initials,
+                coauthors_path,
+            } => write!(
+                f,
+                "Author with initials \"{initials}\" not found in \"{}\"!",
+                coauthors_path.display()
+            ),
+            GitMobError::UnknownGroup {
+                name,
+                coauthors_path,
+            } => write!(
+                f,
+                "Group \"{name}\" not found in \"{}\"!",
+                coauthors_path.display()
+            ),
+            GitMobError::BadRoster { path, message } => {
+                write!(
+                    f,
+                    "Invalid coauthors file \"{}\": {message}",
+                    path.display()
+                )
+            }
+            GitMobError::Io(message) => write!(f, "{message}"),
+            GitMobError::GitConfig(message) => write!(f, "Git config error: {message}"),
+            GitMobError::BadRevision(message) => write!(f, "Bad revision: {message}"),
+            GitMobError::Unhealthy(message) | GitMobError::MissingTrailers(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GitMobError {}
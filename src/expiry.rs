@@ -0,0 +1,310 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::trailers::TrailerFormat;
+use crate::{GitMob, MobScope, MOB_STATE};
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// State kept next to the mob's gitinitials file
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct MobState {
+    /// When the mob was last set with "git mob"
+    pub started: DateTime<FixedOffset>,
+    /// The trailer format the mob was set with, so the hook adds the same trailers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailer: Option<TrailerFormat>,
+}
+
+/// How long a mob lasts, set with `mob.expiry`
+#[derive(Debug, PartialEq, Eq)]
+pub enum MobLifetime {
+    /// A duration such as "90m", "8h" or "1d"
+    Duration(Duration),
+    /// "end-of-day", the mob expires at midnight
+    EndOfDay,
+}
+
+/// Parses a duration in seconds, minutes, hours or days, such as "90m" or "8h"
+pub fn parse_duration(s: &str) -> std::result::Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("\"{s}\" is not a duration like \"90m\" or \"8h\"");
+    let (amount, unit) = s.split_at(s.char_indices().last().map_or(0, |(i, _)| i));
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        _ => None,
+    };
+    duration.ok_or_else(invalid)
+}
+
+impl FromStr for MobLifetime {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "end-of-day" {
+            return Ok(MobLifetime::EndOfDay);
+        }
+
+        parse_duration(s).map(MobLifetime::Duration).map_err(|_| {
+            format!("\"{s}\" is not a duration like \"90m\", \"8h\" or \"end-of-day\"")
+        })
+    }
+}
+
+impl MobLifetime {
+    pub fn is_expired(&self, started: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> bool {
+        match self {
+            MobLifetime::Duration(duration) => now - started >= *duration,
+            MobLifetime::EndOfDay => {
+                started.with_timezone(now.offset()).date_naive() < now.date_naive()
+            }
+        }
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    pub fn get_mob_state(&self, scope: MobScope) -> Result<Option<MobState>> {
+        let state = self
+            .file_actions
+            .read(&self.get_scope_path(scope, MOB_STATE)?)
+            .ok()
+            // a mob set before the state existed simply never expires
+            .and_then(|state| serde_json::from_str(&state).ok());
+        Ok(state)
+    }
+
+    pub(crate) fn write_mob_state(&self, scope: MobScope, state: &MobState) -> Result<()> {
+        let state = serde_json::to_string(state)
+            .map_err(|error| GitMobError::Io(format!("Unable to save the mob state: {error}")))?;
+        self.file_actions
+            .write(&self.get_scope_path(scope, MOB_STATE)?, &state)
+            .map_err(GitMobError::Io)
+    }
+
+    pub(crate) fn remove_mob_state(&self, scope: MobScope) -> Result<()> {
+        self.file_actions
+            .remove(&self.get_scope_path(scope, MOB_STATE)?)
+            .map_err(GitMobError::Io)
+    }
+
+    /// Returns the configured `mob.expiry`, if any
+    pub fn get_mob_lifetime(&self) -> Result<Option<MobLifetime>> {
+        self.get_git_config_value("mob.expiry")?
+            .map(|lifetime| {
+                lifetime
+                    .parse()
+                    .map_err(|error| GitMobError::GitConfig(format!("mob.expiry: {error}")))
+            })
+            .transpose()
+    }
+
+    /// Returns whether an expired mob should make everyone go solo (`mob.onExpire = solo`)
+    /// instead of only warning (`mob.onExpire = warn`, the default)
+    fn is_solo_on_expire(&self) -> Result<bool> {
+        match self.get_git_config_value("mob.onExpire")?.as_deref() {
+            None | Some("warn") => Ok(false),
+            Some("solo") => Ok(true),
+            Some(value) => Err(GitMobError::GitConfig(format!(
+                "mob.onExpire: \"{value}\" should be \"warn\" or \"solo\""
+            ))),
+        }
+    }
+
+    /// Returns when the mob that applies here started, if it has expired
+    pub fn get_expired_mob(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        match self.get_mob_lifetime()? {
+            Some(lifetime) => self.get_expired_mob_with(&lifetime),
+            None => Ok(None),
+        }
+    }
+
+    fn get_expired_mob_with(
+        &self,
+        lifetime: &MobLifetime,
+    ) -> Result<Option<DateTime<FixedOffset>>> {
+        let Some(scope) = self.get_active_scope()? else {
+            return Ok(None);
+        };
+        let Some(state) = self.get_mob_state(scope)? else {
+            return Ok(None);
+        };
+
+        if lifetime.is_expired(state.started, self.clock.now()) {
+            Ok(Some(state.started))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Deals with an expired mob, returning a warning to show if there was one
+    ///
+    /// Depending on `mob.onExpire`, this either only warns or also goes solo.
+    pub fn expire_stale_mob(&self) -> Result<Option<String>> {
+        match self.get_mob_lifetime()? {
+            Some(lifetime) => self.expire_mob(&lifetime, self.is_solo_on_expire()?),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a warning if the mob has expired, without going solo whatever `mob.onExpire` is
+    ///
+    /// This is for commands that only show the mob, such as "git mob print" in a shell prompt.
+    pub fn get_stale_mob_warning(&self) -> Result<Option<String>> {
+        match self.get_mob_lifetime()? {
+            Some(lifetime) => self.expire_mob(&lifetime, false),
+            None => Ok(None),
+        }
+    }
+
+    fn expire_mob(&self, lifetime: &MobLifetime, solo: bool) -> Result<Option<String>> {
+        let Some(started) = self.get_expired_mob_with(lifetime)? else {
+            return Ok(None);
+        };
+
+        let initials = self.get_active_initials()?.join(", ");
+        let started = started.format("%Y-%m-%d %H:%M");
+
+        if !solo {
+            return Ok(Some(format!(
+                "The mob with {initials} started at {started} has expired, run \"git mob <initials>\" to keep mobbing or \"git mob solo\""
+            )));
+        }
+
+        match self.get_active_scope()? {
+            Some(MobScope::Global) => self.clear_global_gitmessage()?,
+            _ => self.write_gitmessage(&[])?,
+        }
+
+        Ok(Some(format!(
+            "The mob with {initials} started at {started} has expired, going solo"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_mob_lifetime() {
+        assert_eq!(
+            Ok(MobLifetime::Duration(Duration::minutes(90))),
+            "90m".parse()
+        );
+        assert_eq!(Ok(MobLifetime::Duration(Duration::hours(8))), "8h".parse());
+        assert_eq!(Ok(MobLifetime::EndOfDay), "end-of-day".parse());
+        assert!("8 hours".parse::<MobLifetime>().is_err());
+        assert!("8é".parse::<MobLifetime>().is_err());
+        assert!("é".parse::<MobLifetime>().is_err());
+        assert!("".parse::<MobLifetime>().is_err());
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let started = time("2024-01-01T09:00:00+00:00");
+
+        let lifetime = MobLifetime::Duration(Duration::hours(8));
+        assert!(!lifetime.is_expired(started, time("2024-01-01T16:59:00+00:00")));
+        assert!(lifetime.is_expired(started, time("2024-01-01T17:00:00+00:00")));
+
+        // the day ends at midnight where the clock is, not where the mob started
+        let lifetime = MobLifetime::EndOfDay;
+        assert!(!lifetime.is_expired(started, time("2024-01-01T23:59:00+00:00")));
+        assert!(lifetime.is_expired(started, time("2024-01-02T00:00:00+00:00")));
+        assert!(!lifetime.is_expired(started, time("2024-01-01T19:00:00-04:00")));
+    }
+
+    #[test]
+    fn test_mob_state() {
+        let gm = get_git_mob();
+
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+        assert_eq!(
+            Some(MobState {
+                started: time("2024-01-01T09:00:00+00:00"),
+                trailer: Some(TrailerFormat::default()),
+            }),
+            gm.get_mob_state(MobScope::Local).unwrap()
+        );
+
+        gm.clock.set("2024-01-02T10:00:00+00:00");
+        gm.write_gitmessage(&[String::from("cd")]).unwrap();
+        assert_eq!(
+            Some(MobState {
+                started: time("2024-01-02T10:00:00+00:00"),
+                trailer: Some(TrailerFormat::default()),
+            }),
+            gm.get_mob_state(MobScope::Local).unwrap()
+        );
+
+        gm.write_gitmessage(&[]).unwrap();
+        assert_eq!(None, gm.get_mob_state(MobScope::Local).unwrap());
+    }
+
+    #[test]
+    fn test_get_expired_mob() {
+        let gm = get_git_mob();
+        let lifetime = MobLifetime::Duration(Duration::hours(8));
+
+        // no mob, nothing to expire
+        assert_eq!(None, gm.get_expired_mob_with(&lifetime).unwrap());
+
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+        gm.clock.set("2024-01-01T16:59:00+00:00");
+        assert_eq!(None, gm.get_expired_mob_with(&lifetime).unwrap());
+
+        gm.clock.set("2024-01-01T17:00:00+00:00");
+        assert_eq!(
+            Some(time("2024-01-01T09:00:00+00:00")),
+            gm.get_expired_mob_with(&lifetime).unwrap()
+        );
+
+        // setting the mob again starts it over
+        gm.write_gitmessage(&[String::from("ab")]).unwrap();
+        assert_eq!(None, gm.get_expired_mob_with(&lifetime).unwrap());
+    }
+
+    #[test]
+    fn test_expire_mob() {
+        let gm = get_git_mob();
+        let lifetime = MobLifetime::EndOfDay;
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+
+        gm.clock.set("2024-01-01T23:59:00+00:00");
+        assert_eq!(None, gm.expire_mob(&lifetime, true).unwrap());
+
+        // warning leaves the mob as it is
+        gm.clock.set("2024-01-02T09:00:00+00:00");
+        assert_eq!(
+            Some(String::from(
+                "The mob with ab, cd started at 2024-01-01 09:00 has expired, run \"git mob <initials>\" to keep mobbing or \"git mob solo\""
+            )),
+            gm.expire_mob(&lifetime, false).unwrap()
+        );
+        assert_eq!("ab,cd\n", gm.get_gitinitials().unwrap());
+
+        assert_eq!(
+            Some(String::from(
+                "The mob with ab, cd started at 2024-01-01 09:00 has expired, going solo"
+            )),
+            gm.expire_mob(&lifetime, true).unwrap()
+        );
+        assert_eq!("\n", gm.get_gitinitials().unwrap());
+        assert_eq!(None, gm.get_mob_state(MobScope::Local).unwrap());
+
+        // once solo there's nothing left to expire
+        assert_eq!(None, gm.expire_mob(&lifetime, true).unwrap());
+    }
+}
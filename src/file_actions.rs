@@ -1,11 +1,13 @@
 use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
+use std::io::ErrorKind;
 use std::path::Path;
 
 pub trait FileActions {
     fn write(&self, path: &Path, s: &str) -> Result<(), String>;
     fn read(&self, path: &Path) -> Result<String, String>;
+    fn remove(&self, path: &Path) -> Result<(), String>;
 }
 
 pub struct FileSystemActions();
@@ -13,6 +15,13 @@ pub struct FileSystemActions();
 impl FileActions for FileSystemActions {
     fn write(&self, path: &Path, s: &str) -> Result<(), String> {
         let path_display = path.display();
+        if let Some(parent) = path.parent() {
+            if let Err(why) = fs::create_dir_all(parent) {
+                return Err(format!(
+                    "couldn't create the directory for {path_display}: {why}"
+                ));
+            }
+        }
         if let Err(why) = fs::write(path, s.as_bytes()) {
             return Err(format!("couldn't write to {path_display}: {why}"));
         }
@@ -32,4 +41,14 @@ impl FileActions for FileSystemActions {
             Ok(_) => Ok(s),
         }
     }
+
+    fn remove(&self, path: &Path) -> Result<(), String> {
+        let path_display = path.display();
+        match fs::remove_file(path) {
+            Err(why) if why.kind() != ErrorKind::NotFound => {
+                Err(format!("couldn't remove {path_display}: {why}"))
+            }
+            _ => Ok(()),
+        }
+    }
 }
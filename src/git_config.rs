@@ -0,0 +1,141 @@
+use crate::error::{GitMobError, Result};
+use dirs::{config_dir, home_dir};
+use gix::bstr::ByteSlice;
+use gix_config::{file::Metadata, File, Source};
+use std::env;
+use std::path::{Path, PathBuf};
+
+fn config_error(config_path: &Path, error: &dyn std::error::Error) -> GitMobError {
+    GitMobError::GitConfig(format!("{}: {error}", config_path.display()))
+}
+
+fn read(config_path: &Path, source: Source) -> Result<File<'static>> {
+    if config_path.exists() {
+        File::from_path_no_includes(config_path.to_path_buf(), source)
+            .map_err(|error| config_error(config_path, &error))
+    } else {
+        Ok(File::new(Metadata::from(source)))
+    }
+}
+
+fn write(config_path: &Path, config: &File) -> Result<()> {
+    let mut config_file =
+        std::fs::File::create(config_path).map_err(|error| config_error(config_path, &error))?;
+    config
+        .write_to(&mut config_file)
+        .map_err(|error| config_error(config_path, &error))
+}
+
+fn split_key(key: &'static str) -> (&'static str, &'static str) {
+    key.split_once('.')
+        .expect("git config keys are always \"section.name\"")
+}
+
+/// Returns the path of the global git config file, the one "git config --global" writes to
+pub fn global_path() -> Result<PathBuf> {
+    if let Ok(path) = env::var("GIT_CONFIG_GLOBAL") {
+        return Ok(PathBuf::from(path));
+    }
+
+    let home_path = home_dir()
+        .ok_or_else(|| GitMobError::Io(String::from("Unable to find your home directory")))?
+        .join(".gitconfig");
+
+    // like git, only use the xdg location if it exists and ~/.gitconfig doesn't
+    let xdg_path = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(config_dir)
+        .map(|dir| dir.join("git").join("config"));
+
+    match xdg_path {
+        Some(xdg_path) if !home_path.exists() && xdg_path.exists() => Ok(xdg_path),
+        _ => Ok(home_path),
+    }
+}
+
+/// Returns the value of `key` in the config file at `config_path`, if it's set
+pub fn get_value(config_path: &Path, source: Source, key: &'static str) -> Result<Option<String>> {
+    Ok(read(config_path, source)?
+        .raw_value(key)
+        .ok()
+        .map(|value| value.to_string()))
+}
+
+/// Sets `key` in the config file at `config_path`, creating the file if needed
+pub fn set_value(config_path: &Path, source: Source, key: &'static str, value: &str) -> Result<()> {
+    let mut config = read(config_path, source)?;
+
+    // don't write to file if we don't have to
+    if let Ok(current) = config.raw_value(key) {
+        if current.as_bstr() == value {
+            return Ok(());
+        }
+    }
+
+    let (section_name, value_name) = split_key(key);
+    config
+        .set_raw_value_by(section_name, None, value_name, value)
+        .map_err(|error| config_error(config_path, &error))?;
+
+    write(config_path, &config)
+}
+
+/// Removes `key` from the config file at `config_path`, along with its section if it's now empty
+pub fn remove_value(config_path: &Path, source: Source, key: &'static str) -> Result<()> {
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let mut config = read(config_path, source)?;
+    let (section_name, value_name) = split_key(key);
+
+    let is_section_empty = match config.section_mut(section_name, None) {
+        Ok(mut section) => {
+            if section.remove(value_name).is_none() {
+                return Ok(());
+            }
+            section.num_values() == 0
+        }
+        Err(_) => return Ok(()),
+    };
+
+    if is_section_empty {
+        config.remove_section(section_name, None);
+    }
+
+    write(config_path, &config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_remove_value() {
+        let dir = tempdir().unwrap();
+        let config_file_path = dir.path().join("config");
+        fs::write(
+            &config_file_path,
+            "[core]\n\tbare = false\n[commit]\n\ttemplate = .git/.gitmessage\n",
+        )
+        .unwrap();
+
+        remove_value(&config_file_path, Source::Local, "commit.template").unwrap();
+
+        assert_eq!(
+            "[core]\n\tbare = false\n",
+            fs::read_to_string(&config_file_path).unwrap()
+        );
+
+        // missing keys and files are fine
+        remove_value(&config_file_path, Source::Local, "commit.template").unwrap();
+        remove_value(
+            &dir.path().join("missing"),
+            Source::Local,
+            "commit.template",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::template::restore_git_template_config;
+use crate::trailers::TrailerFormat;
+use crate::{
+    git_config, to_config_path, GitMob, MobScope, GITINITIALS, GITMESSAGE, MOB_STATE, TIMER_STATE,
+};
+use dirs::config_dir;
+use gix_config::Source;
+use std::path::PathBuf;
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the directory a global mob's files are kept in
+    pub fn get_global_mob_dir(&self) -> Result<PathBuf> {
+        let config_dir = config_dir()
+            .ok_or_else(|| GitMobError::Io(String::from("Unable to find your config directory")))?;
+        Ok(config_dir.join("git-mob"))
+    }
+
+    /// Sets the mob for every repository by pointing the global `commit.template` at a user
+    /// level gitmessage file
+    ///
+    /// A mob set in a repository still takes precedence.
+    pub fn write_global_gitmessage(
+        &self,
+        initials: &[String],
+        trailer: &TrailerFormat,
+    ) -> Result<()> {
+        self.write_mob_files(MobScope::Global, initials, trailer)?;
+
+        self.set_git_template_config(
+            &git_config::global_path()?,
+            Source::User,
+            &to_config_path(&self.get_scope_path(MobScope::Global, GITMESSAGE)?),
+        )
+    }
+
+    /// Removes the global mob, restoring the global `commit.template` that was set before it and
+    /// leaving any other global `commit.template` alone
+    pub fn clear_global_gitmessage(&self) -> Result<()> {
+        let gitmessage_path = self.get_scope_path(MobScope::Global, GITMESSAGE)?;
+        let config_path = git_config::global_path()?;
+
+        if !restore_git_template_config(&config_path, Source::User)? {
+            let template = git_config::get_value(&config_path, Source::User, "commit.template")?;
+            if template.is_some_and(|template| template == to_config_path(&gitmessage_path)) {
+                git_config::remove_value(&config_path, Source::User, "commit.template")?;
+            }
+        }
+
+        for file_name in [GITMESSAGE, GITINITIALS, MOB_STATE, TIMER_STATE] {
+            self.file_actions
+                .remove(&self.get_scope_path(MobScope::Global, file_name)?)
+                .map_err(GitMobError::Io)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+
+    #[test]
+    fn test_global_scope() {
+        let gm = get_git_mob();
+
+        assert_eq!(None, gm.get_active_scope().unwrap());
+
+        gm.write_mob_files(
+            MobScope::Global,
+            &[String::from("ab")],
+            &TrailerFormat::default(),
+        )
+        .unwrap();
+
+        assert_eq!(Some(MobScope::Global), gm.get_active_scope().unwrap());
+        assert_eq!("ab\n", gm.get_gitinitials().unwrap());
+        assert_eq!(
+            "\n\nCo-authored-by: A B <ab@example.com>",
+            gm.get_gitmessage().unwrap()
+        );
+
+        // a mob in the repository takes precedence, even when solo
+        gm.write_mob_files(MobScope::Local, &[], &TrailerFormat::default())
+            .unwrap();
+
+        assert_eq!(Some(MobScope::Local), gm.get_active_scope().unwrap());
+        assert_eq!("\n", gm.get_gitinitials().unwrap());
+        assert_eq!("", gm.get_gitmessage().unwrap());
+    }
+}
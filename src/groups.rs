@@ -0,0 +1,129 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::GitMob;
+use linked_hash_map::LinkedHashMap;
+
+/// Named groups of co-authors, with the initials of their members
+pub type Groups = LinkedHashMap<String, Vec<String>>;
+
+/// Replaces each group name in `initials` with its members, keeping the first time each person
+/// appears
+///
+/// Initials that are also the name of a group are left as they are, so a co-author can't be
+/// shadowed by a group.
+fn expand(initials: &[String], groups: &Groups, is_coauthor: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut expanded: Vec<String> = vec![];
+    for initial in initials {
+        let members = match groups.get(initial) {
+            Some(members) if !is_coauthor(initial) => members.as_slice(),
+            _ => std::slice::from_ref(initial),
+        };
+        for member in members {
+            if !expanded.contains(member) {
+                expanded.push(member.clone());
+            }
+        }
+    }
+    expanded
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the groups in the user's coauthors file
+    pub fn get_user_groups(&self) -> Result<Groups> {
+        Ok(self.read_roster(&self.get_coauthors_path()?)?.groups)
+    }
+
+    /// Returns the user's groups and the trusted repository roster's, the user's taking
+    /// precedence when the names are the same
+    pub fn get_all_groups(&self) -> Result<Groups> {
+        let mut groups = self.get_user_groups()?;
+        for (name, members) in self.get_repo_roster()?.groups {
+            groups.entry(name).or_insert(members);
+        }
+        Ok(groups)
+    }
+
+    /// Writes the groups to the user's coauthors file, keeping its co-authors
+    pub fn write_groups(&self, groups: Groups) -> Result<()> {
+        let path = self.get_coauthors_path()?;
+        let mut roster = self.read_roster(&path)?;
+        roster.groups = groups;
+        self.write_roster_to(&path, None, &roster)
+    }
+
+    /// Replaces the group names in `initials` with the initials of their members, without
+    /// repeating anyone who is in more than one group
+    pub fn expand_groups(&self, initials: &[String]) -> Result<Vec<String>> {
+        let coauthors = self.get_all_coauthors()?;
+        let groups = self.get_all_groups()?;
+        Ok(expand(initials, &groups, |initials| {
+            coauthors.contains_key(initials)
+        }))
+    }
+
+    /// Returns an error unless every one of `members` is a co-author
+    pub fn check_group_members(&self, members: &[String]) -> Result<()> {
+        let coauthors = self.get_all_coauthors()?;
+        match members
+            .iter()
+            .find(|member| !coauthors.contains_key(*member))
+        {
+            Some(member) => Err(GitMobError::UnknownInitials {
+                initials: member.clone(),
+                coauthors_path: self.get_coauthors_path()?,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+
+    fn strings(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand() {
+        let mut groups = Groups::new();
+        groups.insert(String::from("core"), strings(&["ab", "cd"]));
+        groups.insert(String::from("ui"), strings(&["cd", "ef"]));
+        groups.insert(String::from("gh"), strings(&["ab"]));
+        let is_coauthor = |initials: &str| initials == "gh";
+
+        assert_eq!(
+            strings(&["ab", "cd", "ef"]),
+            expand(&strings(&["core", "ui"]), &groups, is_coauthor)
+        );
+        assert_eq!(
+            strings(&["ef", "ab", "cd", "gh"]),
+            expand(&strings(&["ef", "core", "gh", "ab"]), &groups, is_coauthor)
+        );
+    }
+
+    #[test]
+    fn test_write_groups() {
+        let gm = get_git_mob();
+        let mut groups = Groups::new();
+        groups.insert(String::from("pair"), strings(&["ab", "cd"]));
+        gm.write_groups(groups).unwrap();
+
+        assert_eq!(
+            strings(&["ab", "cd"]),
+            gm.get_user_groups().unwrap()["pair"]
+        );
+        assert_eq!(2, gm.get_user_coauthors().unwrap().len());
+        assert_eq!(
+            strings(&["ab", "cd"]),
+            gm.expand_groups(&strings(&["pair", "ab"])).unwrap()
+        );
+        assert!(matches!(
+            gm.check_group_members(&strings(&["ab", "zz"])),
+            Err(GitMobError::UnknownInitials { initials, .. }) if initials == "zz"
+        ));
+    }
+}
@@ -0,0 +1,374 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::message::parse_coauthors;
+use crate::{Author, GitMob};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use gix::index::entry::Mode;
+use gix::revision::plumbing::Spec;
+use gix::{ObjectId, Repository};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// A commit read from the repository's history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    pub id: String,
+    pub author: Author,
+    /// When the commit was authored, in the author's time zone
+    pub time: DateTime<FixedOffset>,
+    pub message: String,
+}
+
+impl CommitInfo {
+    /// Returns the people in the commit's `Co-authored-by` trailers
+    pub fn coauthors(&self) -> Vec<Author> {
+        parse_coauthors(&self.message)
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the commits in a revision or range such as "HEAD" or "main..feature", newest
+    /// first
+    ///
+    /// Only commits authored from the start of `since` until the end of `until` are returned,
+    /// using the current time zone.
+    pub fn get_commits(
+        &self,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<Vec<CommitInfo>> {
+        self.get_commits_in(&self.get_repo()?, revision, since, until)
+    }
+
+    pub(crate) fn get_commits_in(
+        &self,
+        repo: &Repository,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<Vec<CommitInfo>> {
+        let bad_revision = |error: &dyn std::fmt::Display| {
+            GitMobError::BadRevision(format!("\"{revision}\": {error}"))
+        };
+
+        let spec = repo
+            .rev_parse(revision)
+            .map_err(|error| bad_revision(&error))?
+            .detach();
+        let (tip, hidden) = match spec {
+            Spec::Include(tip) => (tip, HashSet::new()),
+            Spec::Range { from, to } => {
+                let hidden = repo
+                    .rev_walk([from])
+                    .all()
+                    .map_err(|error| bad_revision(&error))?
+                    .map(|info| info.map(|info| info.id))
+                    .collect::<std::result::Result<HashSet<ObjectId>, _>>()
+                    .map_err(|error| bad_revision(&error))?;
+                (to, hidden)
+            }
+            _ => {
+                return Err(bad_revision(
+                    &"only a single revision or a range like \"main..feature\" is supported",
+                ))
+            }
+        };
+
+        let offset = *self.clock.now().offset();
+        let start_of = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|time| time.and_local_timezone(offset).single())
+        };
+        let since = since.and_then(start_of);
+        let until = until.and_then(|until| until.succ_opt()).and_then(start_of);
+
+        let mut commits = Vec::new();
+        let walk = repo
+            .rev_walk([tip])
+            .selected(move |id| !hidden.contains(id))
+            .map_err(|error| bad_revision(&error))?;
+        for info in walk {
+            let info = info.map_err(|error| bad_revision(&error))?;
+            let commit = info.object().map_err(|error| bad_revision(&error))?;
+            let Some(commit) = to_commit_info(&commit).map_err(|error| bad_revision(&error))?
+            else {
+                continue;
+            };
+            if since.is_some_and(|since| commit.time < since)
+                || until.is_some_and(|until| commit.time >= until)
+            {
+                continue;
+            }
+            commits.push(commit);
+        }
+
+        Ok(commits)
+    }
+
+    /// Returns the tracked paths that are staged or modified in the worktree
+    pub fn get_changed_paths(&self) -> Result<Vec<String>> {
+        self.get_changed_paths_in(&self.get_repo()?)
+    }
+
+    fn get_changed_paths_in(&self, repo: &Repository) -> Result<Vec<String>> {
+        let git_error = |error: &dyn std::fmt::Display| GitMobError::Io(error.to_string());
+
+        let index = repo.index_or_empty().map_err(|error| git_error(&error))?;
+        let head_tree = match repo.head_commit() {
+            Ok(commit) => Some(commit.tree().map_err(|error| git_error(&error))?),
+            // nothing has been committed yet
+            Err(_) => None,
+        };
+
+        let mut paths = Vec::new();
+        for entry in index.entries() {
+            let path = entry.path(&index).to_string();
+            let head_id = match &head_tree {
+                Some(tree) => tree
+                    .lookup_entry_by_path(&path)
+                    .map_err(|error| git_error(&error))?
+                    .map(|entry| entry.object_id()),
+                None => None,
+            };
+
+            let is_staged = head_id != Some(entry.id);
+            let is_modified = || match repo.work_dir() {
+                Some(work_dir) => is_modified(repo, entry, &work_dir.join(&path)),
+                None => false,
+            };
+            if is_staged || is_modified() {
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Returns the latest `depth` commits of HEAD, newest first
+    pub fn get_latest_commits(&self, depth: usize) -> Result<Vec<CommitInfo>> {
+        let repo = self.get_repo()?;
+        let Ok(head) = repo.head_commit() else {
+            return Ok(vec![]);
+        };
+        let git_error = |error: &dyn std::fmt::Display| GitMobError::Io(error.to_string());
+
+        let mut commits = Vec::new();
+        let walk = repo
+            .rev_walk([head.id])
+            .all()
+            .map_err(|error| git_error(&error))?;
+        for info in walk.take(depth) {
+            let info = info.map_err(|error| git_error(&error))?;
+            let commit = info.object().map_err(|error| git_error(&error))?;
+            commits.extend(to_commit_info(&commit).map_err(|error| git_error(&error))?);
+        }
+        Ok(commits)
+    }
+
+    /// Returns the commits among the latest `depth` commits of HEAD that changed any of `paths`,
+    /// newest first
+    pub fn get_commits_touching(&self, paths: &[String], depth: usize) -> Result<Vec<CommitInfo>> {
+        self.get_commits_touching_in(&self.get_repo()?, paths, depth)
+    }
+
+    fn get_commits_touching_in(
+        &self,
+        repo: &Repository,
+        paths: &[String],
+        depth: usize,
+    ) -> Result<Vec<CommitInfo>> {
+        let Ok(head) = repo.head_commit() else {
+            return Ok(vec![]);
+        };
+        let git_error = |error: &dyn std::fmt::Display| GitMobError::Io(error.to_string());
+        let entry_id = |tree: &gix::Tree<'_>, path: &str| {
+            tree.lookup_entry_by_path(path)
+                .map(|entry| entry.map(|entry| entry.object_id()))
+                .map_err(|error| git_error(&error))
+        };
+
+        let mut touching = Vec::new();
+        let walk = repo
+            .rev_walk([head.id])
+            .all()
+            .map_err(|error| git_error(&error))?;
+        for info in walk.take(depth) {
+            let info = info.map_err(|error| git_error(&error))?;
+            let commit = info.object().map_err(|error| git_error(&error))?;
+            let tree = commit.tree().map_err(|error| git_error(&error))?;
+            // merges are compared with their first parent, like "git log --first-parent"
+            let parent_tree = match info.parent_ids().next() {
+                Some(parent) => Some(
+                    repo.find_commit(parent)
+                        .map_err(|error| git_error(&error))?
+                        .tree()
+                        .map_err(|error| git_error(&error))?,
+                ),
+                None => None,
+            };
+
+            for path in paths {
+                let parent_id = match &parent_tree {
+                    Some(parent_tree) => entry_id(parent_tree, path)?,
+                    None => None,
+                };
+                if entry_id(&tree, path)? != parent_id {
+                    touching.extend(to_commit_info(&commit).map_err(|error| git_error(&error))?);
+                    break;
+                }
+            }
+        }
+
+        Ok(touching)
+    }
+}
+
+/// Reads a commit's author and message, skipping commits with an invalid author date
+fn to_commit_info(
+    commit: &gix::Commit<'_>,
+) -> std::result::Result<Option<CommitInfo>, gix::objs::decode::Error> {
+    let signature = commit.author()?;
+    let Some(time) = FixedOffset::east_opt(signature.time.offset).and_then(|offset| {
+        DateTime::from_timestamp(signature.time.seconds, 0).map(|time| time.with_timezone(&offset))
+    }) else {
+        return Ok(None);
+    };
+
+    Ok(Some(CommitInfo {
+        id: commit.id.to_string(),
+        author: Author {
+            name: signature.name.to_string(),
+            email: signature.email.to_string(),
+        },
+        time,
+        message: commit.message_raw_sloppy().to_string(),
+    }))
+}
+
+/// Returns whether a tracked file in the worktree differs from its index entry
+///
+/// Like git, an unchanged size and modification time is taken to mean the file is unchanged,
+/// otherwise its content is compared.
+fn is_modified(repo: &Repository, entry: &gix::index::Entry, path: &Path) -> bool {
+    if entry.mode != Mode::FILE && entry.mode != Mode::FILE_EXECUTABLE {
+        return false;
+    }
+    let Ok(metadata) = fs::metadata(path) else {
+        // deleted
+        return true;
+    };
+    // git zeroes the size of entries it can't trust, so the content is compared otherwise
+    let is_same_stat = metadata.len() == u64::from(entry.stat.size)
+        && metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .is_some_and(|modified| {
+                modified.as_secs() == u64::from(entry.stat.mtime.secs)
+                    && modified.subsec_nanos() == entry.stat.mtime.nsecs
+            });
+    if is_same_stat {
+        return false;
+    }
+
+    match fs::read(path) {
+        Ok(content) => {
+            gix::objs::compute_hash(repo.object_hash(), gix::object::Kind::Blob, &content)
+                != entry.id
+        }
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit, get_git_mob, git, NOW};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_commits_bad_revision() {
+        let gm = crate::test_utils::get_git_mob();
+
+        let error = gm.get_commits("does-not-exist", None, None).unwrap_err();
+        assert!(matches!(error, GitMobError::BadRevision(_)));
+        assert_eq!(8, error.exit_code());
+    }
+
+    #[test]
+    fn test_get_changed_paths() {
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for file in ["a.txt", "b.txt", "c.txt", "d.txt"] {
+            fs::write(dir.path().join(file), "old\n").unwrap();
+        }
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-q", "-m", "Add files"]);
+
+        fs::write(dir.path().join("a.txt"), "modified\n").unwrap();
+        fs::write(dir.path().join("b.txt"), "staged\n").unwrap();
+        fs::remove_file(dir.path().join("c.txt")).unwrap();
+        fs::write(dir.path().join("e.txt"), "new\n").unwrap();
+        fs::write(dir.path().join("untracked.txt"), "new\n").unwrap();
+        git(dir.path(), &["add", "b.txt", "e.txt"]);
+
+        // d.txt is unchanged and untracked.txt isn't known to git
+        assert_eq!(
+            vec!["a.txt", "b.txt", "c.txt", "e.txt"],
+            gm.get_changed_paths_in(&gix::open(dir.path()).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_commits_touching() {
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        let mut ids = vec![];
+        for (file, content, name, message) in [
+            ("a.txt", "a\n", "E F <ef@example.com>", "Add a"),
+            ("b.txt", "b\n", "E F <ef@example.com>", "Add b"),
+            ("a.txt", "a again\n", "C D <cd@example.com>", "Change a"),
+            ("b.txt", "b again\n", "C D <cd@example.com>", "Change b"),
+        ] {
+            fs::write(dir.path().join(file), content).unwrap();
+            git(dir.path(), &["add", file]);
+            git(
+                dir.path(),
+                &[
+                    "commit", "-q", "--author", name, "--date", NOW, "-m", message,
+                ],
+            );
+            ids.push(git(dir.path(), &["rev-parse", "HEAD"]));
+        }
+        let repo = gix::open(dir.path()).unwrap();
+        let paths = [String::from("a.txt")];
+
+        assert_eq!(
+            vec![
+                commit(&ids[2], author("C D", "cd@example.com"), "Change a\n"),
+                commit(&ids[0], author("E F", "ef@example.com"), "Add a\n"),
+            ],
+            gm.get_commits_touching_in(&repo, &paths, 10).unwrap()
+        );
+        // only the latest commits are looked at
+        assert_eq!(
+            vec![commit(
+                &ids[2],
+                author("C D", "cd@example.com"),
+                "Change a\n"
+            )],
+            gm.get_commits_touching_in(&repo, &paths, 2).unwrap()
+        );
+        assert!(gm
+            .get_commits_touching_in(&repo, &[String::from("c.txt")], 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::message::CommitMessage;
+use crate::GitMob;
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PREPARE_COMMIT_MSG: &str = "prepare-commit-msg";
+pub const COMMIT_MSG: &str = "commit-msg";
+pub const PRE_PUSH: &str = "pre-push";
+
+// Used to recognize our own hook so we never chain to ourselves
+const HOOK_MARKER: &str = "# Installed by git-mob-rs";
+
+// An existing hook is moved here on install and called before ours
+const CHAINED_SUFFIX: &str = ".pre-git-mob";
+
+// Commits that reuse messages from elsewhere and should not get the mob's trailers
+const SKIPPED_SOURCES: [&str; 2] = ["merge", "squash"];
+pub(crate) const SKIPPED_PREFIXES: [&str; 3] = ["fixup!", "squash!", "amend!"];
+
+/// The hooks git mob can install
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Adds the mob's trailers to each commit message
+    PrepareCommitMsg,
+    /// Rejects commits whose message is missing the mob's trailers
+    CommitMsg,
+    /// Rejects pushes of commits that are missing the mob's trailers
+    PrePush,
+}
+
+impl Hook {
+    /// Returns the hook's file name, which git runs it by
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PrepareCommitMsg => PREPARE_COMMIT_MSG,
+            Hook::CommitMsg => COMMIT_MSG,
+            Hook::PrePush => PRE_PUSH,
+        }
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the hooks directory, respecting `core.hooksPath`
+    pub fn get_hooks_dir(&self) -> Result<PathBuf> {
+        let repo = self.get_repo()?;
+        let hooks_path = match repo.config_snapshot().trusted_path("core.hooksPath") {
+            Some(Ok(path)) => Some(path.into_owned()),
+            Some(Err(error)) => return Err(GitMobError::GitConfig(error.to_string())),
+            None => None,
+        };
+
+        Ok(match hooks_path {
+            // relative paths are relative to where git runs hooks, the root of the worktree
+            Some(path) if path.is_relative() => repo.work_dir().unwrap_or(repo.path()).join(path),
+            Some(path) => path,
+            None => repo.common_dir().join("hooks"),
+        })
+    }
+
+    pub fn install_hook(&self, hook: Hook) -> Result<String> {
+        self.install_hook_in(&self.get_hooks_dir()?, hook.name())
+    }
+
+    pub fn uninstall_hook(&self, hook: Hook) -> Result<String> {
+        self.uninstall_hook_in(&self.get_hooks_dir()?, hook.name())
+    }
+
+    fn install_hook_in(&self, hooks_dir: &Path, name: &str) -> Result<String> {
+        let hook_path = hooks_dir.join(name);
+        let chained_path = hooks_dir.join(format!("{name}{CHAINED_SUFFIX}"));
+
+        if is_our_hook(&hook_path) {
+            return Ok(format!(
+                "{name} hook is already installed in {}",
+                hooks_dir.display()
+            ));
+        }
+
+        fs::create_dir_all(hooks_dir).map_err(|error| {
+            GitMobError::Io(format!("Unable to create {}: {error}", hooks_dir.display()))
+        })?;
+
+        let mut message = String::new();
+        if hook_path.exists() {
+            if chained_path.exists() {
+                return Err(GitMobError::Io(format!(
+                    "Both {} and {} already exist, refusing to overwrite either of them",
+                    hook_path.display(),
+                    chained_path.display()
+                )));
+            }
+            fs::rename(&hook_path, &chained_path).map_err(|error| {
+                GitMobError::Io(format!("Unable to move the existing {name} hook: {error}"))
+            })?;
+            message.push_str(&format!(
+                "The existing {name} hook was moved to {} and will still run\n",
+                chained_path.display()
+            ));
+        }
+
+        fs::write(&hook_path, hook_script(name)).map_err(|error| {
+            GitMobError::Io(format!("Unable to write {}: {error}", hook_path.display()))
+        })?;
+        set_executable(&hook_path).map_err(|error| {
+            GitMobError::Io(format!(
+                "Unable to make {} executable: {error}",
+                hook_path.display()
+            ))
+        })?;
+
+        message.push_str(&format!(
+            "{name} hook has been installed in {}",
+            hooks_dir.display()
+        ));
+        Ok(message)
+    }
+
+    fn uninstall_hook_in(&self, hooks_dir: &Path, name: &str) -> Result<String> {
+        let hook_path = hooks_dir.join(name);
+        let chained_path = hooks_dir.join(format!("{name}{CHAINED_SUFFIX}"));
+
+        if !is_our_hook(&hook_path) {
+            return Ok(format!(
+                "{name} hook is not installed in {}",
+                hooks_dir.display()
+            ));
+        }
+
+        fs::remove_file(&hook_path).map_err(|error| {
+            GitMobError::Io(format!("Unable to remove {}: {error}", hook_path.display()))
+        })?;
+
+        if chained_path.exists() {
+            fs::rename(&chained_path, &hook_path).map_err(|error| {
+                GitMobError::Io(format!(
+                    "Unable to restore the previous {name} hook: {error}"
+                ))
+            })?;
+            return Ok(format!(
+                "{name} hook has been uninstalled and the previous hook restored"
+            ));
+        }
+
+        Ok(format!("{name} hook has been uninstalled"))
+    }
+
+    /// Appends the active mob's trailers to the commit message file given to the
+    /// `prepare-commit-msg` hook
+    ///
+    /// `source` is the second argument git passes to the hook, such as "message" or "merge".
+    pub fn prepare_commit_msg(&self, message_path: &Path, source: Option<&str>) -> Result<()> {
+        if source.is_some_and(|source| SKIPPED_SOURCES.contains(&source)) {
+            return Ok(());
+        }
+
+        if let Some(warning) = self.expire_stale_mob()? {
+            eprintln!("Warning: {warning}");
+        }
+
+        let initials = self.get_active_initials()?;
+        if initials.is_empty() {
+            return Ok(());
+        }
+
+        let message = self
+            .file_actions
+            .read(message_path)
+            .map_err(GitMobError::Io)?;
+
+        let subject = message.trim_start().lines().next().unwrap_or_default();
+        if SKIPPED_PREFIXES
+            .iter()
+            .any(|prefix| subject.starts_with(prefix))
+        {
+            return Ok(());
+        }
+
+        let trailers = self.get_coauthor_trailers(&initials)?;
+        let comment = self.get_comment_prefix()?;
+        let new_message = CommitMessage::parse(&message, Some(&comment)).merge(&trailers);
+
+        if new_message != message {
+            self.file_actions
+                .write(message_path, &new_message)
+                .map_err(GitMobError::Io)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn hook_script(name: &str) -> String {
+    if name == PRE_PUSH {
+        // git gives pre-push the refs on stdin, which both hooks have to read, so it's kept to
+        // give each of them, with the newline that ends the last line
+        return format!(
+            "#!/bin/sh
+{HOOK_MARKER}, remove with: git mob hook uninstall {name}
+input=$(cat)
+if [ -x \"$0{CHAINED_SUFFIX}\" ]; then
+    printf \"${{input:+%s\\n}}\" \"$input\" | \"$0{CHAINED_SUFFIX}\" \"$@\" || exit $?
+fi
+command -v git-mob >/dev/null 2>&1 || exit 0
+printf \"${{input:+%s\\n}}\" \"$input\" | git-mob hook {name} \"$@\"
+"
+        );
+    }
+
+    format!(
+        "#!/bin/sh
+{HOOK_MARKER}, remove with: git mob hook uninstall {name}
+if [ -x \"$0{CHAINED_SUFFIX}\" ]; then
+    \"$0{CHAINED_SUFFIX}\" \"$@\" || exit $?
+fi
+command -v git-mob >/dev/null 2>&1 || exit 0
+exec git-mob hook {name} \"$@\"
+"
+    )
+}
+
+fn is_our_hook(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|script| script.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    const TRAILERS: &str =
+        "Co-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>\n";
+
+    #[test]
+    fn test_prepare_commit_msg() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+        let message_path = Path::new("COMMIT_EDITMSG");
+
+        gm.file_actions
+            .write(message_path, "Add feature\n")
+            .unwrap();
+        gm.prepare_commit_msg(message_path, Some("message"))
+            .unwrap();
+
+        assert_eq!(
+            format!("Add feature\n\n{TRAILERS}"),
+            gm.file_actions.read(message_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_msg_amend() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+        let message_path = Path::new("COMMIT_EDITMSG");
+        let message = format!("Add feature\n\n{TRAILERS}\n# Please enter the commit message\n");
+
+        gm.file_actions.write(message_path, &message).unwrap();
+        gm.prepare_commit_msg(message_path, Some("commit")).unwrap();
+
+        assert_eq!(message, gm.file_actions.read(message_path).unwrap());
+    }
+
+    #[test]
+    fn test_prepare_commit_msg_verbose() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+        let message_path = Path::new("COMMIT_EDITMSG");
+        let diff = "# ------------------------ >8 ------------------------
+# Do not modify or remove the line above.
+# Everything below it will be ignored.
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1,2 @@
+ # README
++Foo: bar
+";
+
+        gm.file_actions
+            .write(
+                message_path,
+                &format!("Add feature\n\n# Please enter the commit message\n{diff}"),
+            )
+            .unwrap();
+        gm.prepare_commit_msg(message_path, Some("message"))
+            .unwrap();
+
+        assert_eq!(
+            format!("Add feature\n\n{TRAILERS}\n# Please enter the commit message\n{diff}"),
+            gm.file_actions.read(message_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_msg_skipped_sources() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
+        let message_path = Path::new("COMMIT_EDITMSG");
+
+        gm.file_actions
+            .write(message_path, "Merge branch 'main'\n")
+            .unwrap();
+        gm.prepare_commit_msg(message_path, Some("merge")).unwrap();
+        gm.prepare_commit_msg(message_path, Some("squash")).unwrap();
+        assert_eq!(
+            "Merge branch 'main'\n",
+            gm.file_actions.read(message_path).unwrap()
+        );
+
+        gm.file_actions
+            .write(message_path, "fixup! Add feature\n")
+            .unwrap();
+        gm.prepare_commit_msg(message_path, Some("message"))
+            .unwrap();
+        assert_eq!(
+            "fixup! Add feature\n",
+            gm.file_actions.read(message_path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_install_and_uninstall_hook() {
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        let hooks_dir = dir.path().join("hooks");
+        let hook_path = hooks_dir.join(PREPARE_COMMIT_MSG);
+
+        gm.install_hook_in(&hooks_dir, PREPARE_COMMIT_MSG).unwrap();
+        assert!(is_our_hook(&hook_path));
+        assert!(fs::read_to_string(&hook_path)
+            .unwrap()
+            .contains("remove with: git mob hook uninstall prepare-commit-msg\n"));
+
+        assert_eq!(
+            format!(
+                "{PREPARE_COMMIT_MSG} hook is already installed in {}",
+                hooks_dir.display()
+            ),
+            gm.install_hook_in(&hooks_dir, PREPARE_COMMIT_MSG).unwrap()
+        );
+
+        gm.uninstall_hook_in(&hooks_dir, PREPARE_COMMIT_MSG)
+            .unwrap();
+        assert!(!hook_path.exists());
+    }
+
+    #[test]
+    fn test_install_hook_chains_existing_hook() {
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        let hooks_dir = dir.path();
+        let hook_path = hooks_dir.join(PREPARE_COMMIT_MSG);
+        let chained_path = hooks_dir.join(format!("{PREPARE_COMMIT_MSG}{CHAINED_SUFFIX}"));
+        let existing_hook = "#!/bin/sh\necho existing\n";
+
+        fs::write(&hook_path, existing_hook).unwrap();
+
+        gm.install_hook_in(hooks_dir, PREPARE_COMMIT_MSG).unwrap();
+        assert!(is_our_hook(&hook_path));
+        assert_eq!(existing_hook, fs::read_to_string(&chained_path).unwrap());
+
+        gm.uninstall_hook_in(hooks_dir, PREPARE_COMMIT_MSG).unwrap();
+        assert_eq!(existing_hook, fs::read_to_string(&hook_path).unwrap());
+        assert!(!chained_path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pre_push_hook_chains_stdin() {
+        use std::process::{Command, Stdio};
+
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        let hooks_dir = dir.path().join("hooks");
+        let bin_dir = dir.path().join("bin");
+        fs::create_dir_all(&hooks_dir).unwrap();
+        fs::create_dir_all(&bin_dir).unwrap();
+        let hook_path = hooks_dir.join(PRE_PUSH);
+
+        // the existing hook and git mob each save what they're given
+        let saving_script = |path: &Path, saved: &str| {
+            fs::write(
+                path,
+                format!("#!/bin/sh\necho \"$@\" > {saved}\ncat >> {saved}\n"),
+            )
+            .unwrap();
+            set_executable(path).unwrap();
+        };
+        saving_script(&hook_path, "\"$0.saved\"");
+        saving_script(
+            &bin_dir.join("git-mob"),
+            &dir.path().join("git-mob.saved").display().to_string(),
+        );
+        gm.install_hook_in(&hooks_dir, PRE_PUSH).unwrap();
+        assert!(fs::read_to_string(&hook_path)
+            .unwrap()
+            .contains("remove with: git mob hook uninstall pre-push\n"));
+
+        let run = |input: &str| {
+            let mut hook = Command::new(&hook_path)
+                .args(["origin", "https://example.com/repo.git"])
+                .env(
+                    "PATH",
+                    format!("{}:{}", bin_dir.display(), std::env::var("PATH").unwrap()),
+                )
+                .stdin(Stdio::piped())
+                .spawn()
+                .unwrap();
+            std::io::Write::write_all(&mut hook.stdin.take().unwrap(), input.as_bytes()).unwrap();
+            assert!(hook.wait().unwrap().success());
+            (
+                fs::read_to_string(hooks_dir.join(format!("{PRE_PUSH}{CHAINED_SUFFIX}.saved")))
+                    .unwrap(),
+                fs::read_to_string(dir.path().join("git-mob.saved")).unwrap(),
+            )
+        };
+
+        let input = "refs/heads/main 1234 refs/heads/main 5678\nrefs/heads/other 9abc refs/heads/other 0000\n";
+        assert_eq!(
+            (
+                format!("origin https://example.com/repo.git\n{input}"),
+                format!("hook pre-push origin https://example.com/repo.git\n{input}")
+            ),
+            run(input)
+        );
+
+        // pushing nothing gives both of them nothing
+        assert_eq!(
+            (
+                String::from("origin https://example.com/repo.git\n"),
+                String::from("hook pre-push origin https://example.com/repo.git\n")
+            ),
+            run("")
+        );
+    }
+}
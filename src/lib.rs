@@ -1,247 +1,521 @@
-pub mod exit_with_error;
+pub mod audit;
+pub mod clock;
+pub mod completions;
+pub mod credits;
+pub mod doctor;
+pub mod enforce;
+pub mod error;
+pub mod expiry;
 pub mod file_actions;
-
+pub mod git_config;
+pub mod global;
+pub mod groups;
+pub mod history;
+pub mod hooks;
+pub mod links;
+pub mod lint;
+pub mod message;
+pub mod output;
+pub mod picker;
+pub mod rewrite;
+pub mod roster;
+pub mod squash;
+pub mod stats;
+pub mod suggest;
+pub mod template;
+pub mod timer;
+pub mod trailers;
+
+use clock::{Clock, SystemClock};
 use dirs::{config_dir, home_dir};
-use exit_with_error::{ExitWithError, ExitWithErrorImpl};
+use error::{GitMobError, Result};
+use expiry::MobState;
 use file_actions::{FileActions, FileSystemActions};
-use gix::bstr::ByteSlice;
-use gix::{self, config, Repository};
+use gix::{self, Repository};
 use gix_config::Source;
 use linked_hash_map::LinkedHashMap;
+use message::CommitMessage;
+use roster::RosterFormat;
 use serde::{Deserialize, Serialize};
-use serde_json::to_string_pretty;
 use std::env;
-use std::fs::File;
-use std::path::{Path, PathBuf};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use template::{format_gitmessage, PREVIOUS_TEMPLATE};
+use trailers::TrailerFormat;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Coauthors {
+    /// The JSON schema editors validate the file with
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
     pub coauthors: LinkedHashMap<String, Author>,
+    /// Named groups of co-authors, such as a team, by their initials
+    #[serde(default, skip_serializing_if = "LinkedHashMap::is_empty")]
+    pub groups: LinkedHashMap<String, Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Author {
     pub name: String,
     pub email: String,
 }
 
+pub const GITMESSAGE: &str = ".gitmessage";
+pub const GITINITIALS: &str = ".gitinitials";
+pub const MOB_STATE: &str = ".gitmobstate";
+pub const TIMER_STATE: &str = ".gitmobtimer";
+
+/// Where a mob is set
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MobScope {
+    /// Set for the current repository with "git mob"
+    Local,
+    /// Set for every repository with "git mob --global"
+    Global,
+}
+
+impl fmt::Display for MobScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MobScope::Local => write!(f, "local"),
+            MobScope::Global => write!(f, "global"),
+        }
+    }
+}
+
 // Use dependency injection to put the real impl for Default and the mock impl in tests
-// This doesn't use dyn Box to make it slightly more performant and to
-// avoid object safe trait issues when using ExitWithError.
+// This doesn't use dyn Box to make it slightly more performant.
 // But this approach does make it a bit more verbose...
-pub struct GitMob<T: FileActions, U: ExitWithError> {
+pub struct GitMob<T: FileActions, C: Clock> {
     pub file_actions: T,
-    pub exit_with_error: U,
+    pub clock: C,
 }
 
-impl Default for GitMob<FileSystemActions, ExitWithErrorImpl> {
+impl Default for GitMob<FileSystemActions, SystemClock> {
     fn default() -> Self {
         GitMob {
             file_actions: FileSystemActions(),
-            exit_with_error: ExitWithErrorImpl(),
+            clock: SystemClock(),
         }
     }
 }
 
-impl<T: FileActions, U: ExitWithError> GitMob<T, U> {
-    pub fn get_repo(&self) -> Repository {
-        gix::discover(".").unwrap_or_else(|_| {
-            self.exit_with_error.message("Not in a git repository");
-        })
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    pub fn get_repo(&self) -> Result<Repository> {
+        gix::discover(".").map_err(|_| GitMobError::NotInRepo)
     }
 
-    pub fn get_gitmessage_path(&self) -> PathBuf {
-        self.get_repo().path().join(".gitmessage")
+    /// Returns whether each worktree of the repository has its own mob
+    ///
+    /// By default a mob is shared by all worktrees, set `mob.perWorktree` to change this.
+    pub(crate) fn is_per_worktree(&self, repo: &Repository) -> bool {
+        repo.config_snapshot()
+            .boolean("mob.perWorktree")
+            .unwrap_or(false)
     }
 
-    pub fn get_gitinitials_path(&self) -> PathBuf {
-        self.get_repo().path().join(".gitinitials")
+    /// Returns the git directory the mob's files are kept in
+    ///
+    /// This is the common directory shared by linked worktrees and submodules' separate git
+    /// directories, unless the mob is kept per worktree.
+    pub fn get_mob_dir(&self) -> Result<PathBuf> {
+        Ok(self.mob_dir(&self.get_repo()?))
     }
 
-    pub fn write_gitmessage(&self, initials: &[String]) {
-        let authors = if initials.is_empty() {
-            // for git solo
-            String::new()
+    fn mob_dir(&self, repo: &Repository) -> PathBuf {
+        if self.is_per_worktree(repo) {
+            repo.path().to_path_buf()
         } else {
-            let coauthors = self.get_all_coauthors();
-
-            let name_emails = initials
-                .iter()
-                .map(|initial| {
-                    if coauthors.contains_key(initial) {
-                        let Author { name, email } = &coauthors[initial];
-                        format!("Co-authored-by: {name} <{email}>")
-                    } else {
-                        let coauthors_path = self.get_coauthors_path();
-                        let coauthors_path = coauthors_path.as_path().display();
-                        self.exit_with_error.message(&format!(
-                            "Author with initials \"{initial}\" not found in \"{coauthors_path}\"!"
-                        ));
-                    }
-                })
-                .collect::<Vec<String>>()
-                .join("\n");
-            format!("\n\n{name_emails}")
-        };
+            repo.common_dir().to_path_buf()
+        }
+    }
 
-        let initials_str = initials.join(",");
+    /// Returns the `commit.template` value for the repository's local mob
+    ///
+    /// A shared mob's template is set in the config every worktree reads, where a relative
+    /// ".git/.gitmessage" would be wrong in linked worktrees, so it's always absolute.
+    pub(crate) fn get_template_config_value(&self, repo: &Repository) -> String {
+        let gitmessage_path = self.mob_dir(repo).join(GITMESSAGE);
+        if self.is_per_worktree(repo) {
+            get_template_value(repo.work_dir(), &gitmessage_path)
+        } else {
+            to_config_path(&gitmessage_path)
+        }
+    }
 
-        let gitmessage_path = self.get_gitmessage_path();
-        let gitinitials_path = self.get_gitinitials_path();
+    /// Returns the path of one of the mob's files, such as `GITMESSAGE`, for the given scope
+    pub fn get_scope_path(&self, scope: MobScope, file_name: &str) -> Result<PathBuf> {
+        match scope {
+            MobScope::Local => Ok(self.get_mob_dir()?.join(file_name)),
+            MobScope::Global => Ok(self.get_global_mob_dir()?.join(file_name)),
+        }
+    }
 
-        self.file_actions.write(&gitmessage_path, &authors).unwrap();
-        self.file_actions
-            .write(&gitinitials_path, &format!("{initials_str}\n"))
-            .unwrap();
+    pub fn get_gitmessage_path(&self) -> Result<PathBuf> {
+        self.get_scope_path(MobScope::Local, GITMESSAGE)
+    }
 
-        self.set_git_template();
+    pub fn get_gitinitials_path(&self) -> Result<PathBuf> {
+        self.get_scope_path(MobScope::Local, GITINITIALS)
+    }
+
+    pub fn write_gitmessage(&self, initials: &[String]) -> Result<()> {
+        self.write_gitmessage_with_trailer(initials, &self.get_trailer_format()?)
+    }
+
+    /// Sets the mob for the repository, with a trailer format other than the configured one
+    pub fn write_gitmessage_with_trailer(
+        &self,
+        initials: &[String],
+        trailer: &TrailerFormat,
+    ) -> Result<()> {
+        // make sure we're in a repository before resolving initials
+        self.get_repo()?;
+
+        self.write_mob_files(MobScope::Local, initials, trailer)?;
+
+        // going solo restores the user's own template, which still takes precedence over a
+        // global mob
+        if initials.is_empty() && self.restore_git_template()? {
+            return Ok(());
+        }
+        self.set_git_template()
     }
 
-    pub fn write_coauthors(&self, coauthors: LinkedHashMap<String, Author>) {
-        let coauthors_path = self.get_coauthors_path();
-        let coauthors = Coauthors { coauthors };
+    fn write_mob_files(
+        &self,
+        scope: MobScope,
+        initials: &[String],
+        trailer: &TrailerFormat,
+    ) -> Result<()> {
+        let initials = &self.expand_groups(initials)?;
+        let trailers = self.format_coauthor_trailers(initials, trailer)?;
+        let authors = format_gitmessage(
+            &self.get_previous_template_content(scope)?,
+            &trailers,
+            &self.get_comment_prefix()?,
+        );
+
+        let initials_str = initials.join(",");
 
         self.file_actions
-            .write(&coauthors_path, &to_string_pretty(&coauthors).unwrap())
-            .unwrap();
+            .write(&self.get_scope_path(scope, GITMESSAGE)?, &authors)
+            .map_err(GitMobError::Io)?;
+        self.file_actions
+            .write(
+                &self.get_scope_path(scope, GITINITIALS)?,
+                &format!("{initials_str}\n"),
+            )
+            .map_err(GitMobError::Io)?;
+
+        if initials.is_empty() {
+            self.remove_timer(scope)?;
+            self.remove_mob_state(scope)
+        } else {
+            self.write_mob_state(
+                scope,
+                &MobState {
+                    started: self.clock.now(),
+                    trailer: Some(trailer.clone()),
+                },
+            )
+        }
     }
 
-    fn set_git_template(&self) {
-        let repo = self.get_repo();
-        let repo_git_path = repo.path();
-        let config_path = repo_git_path.join("config");
+    /// Returns a trailer for each of the initials, in the given order, in the format the active
+    /// mob was set with
+    pub fn get_coauthor_trailers(&self, initials: &[String]) -> Result<Vec<String>> {
+        self.format_coauthor_trailers(initials, &self.get_active_trailer_format()?)
+    }
 
-        self.set_git_template_config(&config_path);
+    /// Returns a trailer for each of the initials, in the given order and format
+    pub fn format_coauthor_trailers(
+        &self,
+        initials: &[String],
+        trailer: &TrailerFormat,
+    ) -> Result<Vec<String>> {
+        let coauthors = self.get_all_coauthors()?;
+
+        initials
+            .iter()
+            .map(|initial| match coauthors.get(initial) {
+                Some(author) => Ok(trailer.format(initial, author)),
+                None => Err(GitMobError::UnknownInitials {
+                    initials: initial.to_string(),
+                    coauthors_path: self.get_coauthors_path()?,
+                }),
+            })
+            .collect()
     }
 
-    fn set_git_template_config(&self, config_path: &PathBuf) {
-        let mut config =
-            gix_config::File::from_path_no_includes(config_path.to_path_buf(), Source::Local)
-                .unwrap();
+    /// Writes the user's coauthors file, keeping its format
+    pub fn write_coauthors(&self, coauthors: LinkedHashMap<String, Author>) -> Result<()> {
+        self.write_coauthors_to(&self.get_coauthors_path()?, None, coauthors)
+    }
 
-        let template = ".git/.gitmessage";
+    fn set_git_template(&self) -> Result<()> {
+        let repo = self.get_repo()?;
+        let template = self.get_template_config_value(&repo);
+        let worktree_config_path = repo.path().join("config.worktree");
+
+        if self.is_per_worktree(&repo) {
+            // per worktree config is only read by git when the extension is enabled
+            git_config::set_value(
+                &repo.common_dir().join("config"),
+                Source::Local,
+                "extensions.worktreeConfig",
+                "true",
+            )?;
+            self.set_git_template_config(&worktree_config_path, Source::Worktree, &template)
+        } else {
+            // a template left over from a per worktree mob would hide the shared one
+            git_config::remove_value(&worktree_config_path, Source::Worktree, "commit.template")?;
+            self.set_git_template_config(
+                &repo.common_dir().join("config"),
+                Source::Local,
+                &template,
+            )
+        }
+    }
 
-        // don't write to file if we don't have to
-        if let Ok(value) = config.raw_value("commit.template") {
-            if value.as_bstr() == template {
-                return;
+    /// Points `commit.template` in the config file at the mob's gitmessage, keeping any other
+    /// template that was set in `mob.previousTemplate` so it can be restored
+    fn set_git_template_config(
+        &self,
+        config_path: &Path,
+        source: Source,
+        template: &str,
+    ) -> Result<()> {
+        if let Some(previous) = git_config::get_value(config_path, source, "commit.template")? {
+            if !self.is_mob_template(&previous)? {
+                git_config::set_value(config_path, source, PREVIOUS_TEMPLATE, &previous)?;
             }
         }
-
-        config.set_raw_value(&"commit.template", template).unwrap();
-
-        let mut config_file = File::create(config_path).unwrap();
-        config.write_to(&mut config_file).unwrap();
+        git_config::set_value(config_path, source, "commit.template", template)
     }
 
     /// Returns the coauthors path
     ///
     /// This supports both xdg (prioritized) or if the config is in the home directory (like
-    /// git-mob).
-    pub fn get_coauthors_path(&self) -> PathBuf {
+    /// git-mob), in any of the roster formats, such as "git-coauthors.toml".
+    pub fn get_coauthors_path(&self) -> Result<PathBuf> {
         if let Ok(path) = env::var("GITMOB_COAUTHORS_PATH") {
-            return PathBuf::from(path);
+            return Ok(PathBuf::from(path));
         }
 
         let file_name = "git-coauthors";
 
         // most likely on fresh install after first use
-        let mut coauthors_path = config_dir().unwrap();
-        coauthors_path.push(file_name);
-        if coauthors_path.exists() {
-            return coauthors_path;
+        let config_dir = config_dir()
+            .ok_or_else(|| GitMobError::Io(String::from("Unable to find your config directory")))?;
+        if let Some(coauthors_path) = find_roster(&config_dir, file_name) {
+            return Ok(coauthors_path);
         }
 
         // else check home dir - if it doesn't exist (like a fresh install) use xdg instead
-        let mut home_coauthors_path = home_dir().unwrap();
-        home_coauthors_path.push(format!(".{file_name}"));
-        if home_coauthors_path.exists() {
-            home_coauthors_path
-        } else {
-            coauthors_path
+        let home_dir = home_dir()
+            .ok_or_else(|| GitMobError::Io(String::from("Unable to find your home directory")))?;
+        match find_roster(&home_dir, &format!(".{file_name}")) {
+            Some(home_coauthors_path) => Ok(home_coauthors_path),
+            None => Ok(config_dir.join(file_name)),
         }
     }
 
-    fn get_git_config(&self, cfg: &config::Snapshot, key: &str) -> String {
-        match cfg.string(key) {
-            Some(value) => value.to_string(),
+    fn get_git_config(&self, value: Option<String>, key: &str) -> String {
+        match value {
+            Some(value) => value,
             None => {
                 // these errors should only really happen in ci
-                println!("Warning: your git config \"{key}\" is missing!");
+                eprintln!("Warning: your git config \"{key}\" is missing!");
                 String::from("")
             }
         }
     }
 
-    pub fn get_git_user(&self) -> String {
-        let repo = self.get_repo();
-        let cfg = repo.config_snapshot();
+    /// Returns a value from the git config, using the global git config when not in a
+    /// repository
+    pub fn get_git_config_value(&self, key: &str) -> Result<Option<String>> {
+        match self.get_repo() {
+            Ok(repo) => Ok(repo
+                .config_snapshot()
+                .string(key)
+                .map(|value| value.to_string())),
+            Err(GitMobError::NotInRepo) => {
+                let cfg = gix_config::File::from_globals()
+                    .map_err(|error| GitMobError::GitConfig(error.to_string()))?;
+                Ok(cfg.string(key).map(|value| value.to_string()))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns who you are in git, warning about a missing name or email
+    pub fn get_git_author(&self) -> Result<Author> {
+        Ok(Author {
+            name: self.get_git_config(self.get_git_config_value("user.name")?, "user.name"),
+            email: self.get_git_config(self.get_git_config_value("user.email")?, "user.email"),
+        })
+    }
 
-        let user = self.get_git_config(&cfg, "user.name");
-        let email = self.get_git_config(&cfg, "user.email");
+    pub fn get_git_user(&self) -> Result<String> {
+        let Author { name, email } = self.get_git_author()?;
 
-        format!("{user} <{email}>")
+        Ok(format!("{name} <{email}>"))
     }
 
-    pub fn get_gitmessage(&self) -> String {
-        self.file_actions
-            .read(&self.get_gitmessage_path())
-            .unwrap_or_else(|error| {
-                self.exit_with_error.message(&format!(
-                    "Make sure to run 'git mob <initials>' first.\n\nError: {}",
-                    error
-                ));
-            })
+    /// Returns where the mob that applies here is set, if anywhere
+    ///
+    /// A mob set in the repository (including going solo) takes precedence over a global mob.
+    pub fn get_active_scope(&self) -> Result<Option<MobScope>> {
+        if gix::discover(".").is_ok()
+            && self
+                .file_actions
+                .read(&self.get_gitinitials_path()?)
+                .is_ok()
+        {
+            return Ok(Some(MobScope::Local));
+        }
+
+        if self
+            .file_actions
+            .read(&self.get_scope_path(MobScope::Global, GITINITIALS)?)
+            .is_ok()
+        {
+            return Ok(Some(MobScope::Global));
+        }
+
+        Ok(None)
+    }
+
+    pub fn get_gitmessage(&self) -> Result<String> {
+        let scope = self.get_active_scope()?.unwrap_or(MobScope::Local);
+        let gitmessage_path = self.get_scope_path(scope, GITMESSAGE)?;
+
+        self.file_actions.read(&gitmessage_path).map_err(|error| {
+            GitMobError::Io(format!(
+                "Make sure to run 'git mob <initials>' first.\n\nError: {error}"
+            ))
+        })
     }
 
-    pub fn get_gitinitials(&self) -> String {
+    pub fn get_gitinitials(&self) -> Result<String> {
         // git-mob-print -i (using in a shell prompt) situations:
         // - Not in the repo
         // - In repo, but hasn't run git-mob
 
         if gix::discover(".").is_err() {
-            return String::new();
+            return Ok(String::new());
         }
 
-        self.file_actions
-            .read(&self.get_gitinitials_path())
-            .unwrap_or_default()
+        let scope = self.get_active_scope()?.unwrap_or(MobScope::Local);
+        let gitinitials_path = self.get_scope_path(scope, GITINITIALS)?;
+
+        Ok(self
+            .file_actions
+            .read(&gitinitials_path)
+            .unwrap_or_default())
     }
 
-    pub fn get_formatted_gitmessage(&self) -> String {
-        let git_user = self.get_git_user();
+    /// Returns the initials of the current mob, or an empty list when solo
+    pub fn get_active_initials(&self) -> Result<Vec<String>> {
+        Ok(self
+            .get_gitinitials()?
+            .trim()
+            .split(',')
+            .map(str::trim)
+            .filter(|initial| !initial.is_empty())
+            .map(String::from)
+            .collect())
+    }
 
-        let gitmessage = self.get_gitmessage();
-        let gitmessage = gitmessage.trim();
+    pub fn get_formatted_gitmessage(&self) -> Result<String> {
+        let git_user = self.get_git_user()?;
 
-        if gitmessage.is_empty() {
-            git_user
+        let gitmessage = self.get_gitmessage()?;
+        let comment = self.get_comment_prefix()?;
+        // the trailers are the trailer block, below the user's own template
+        let trailers = if self.get_active_initials()?.is_empty() {
+            vec![]
         } else {
-            format!("{git_user}\n{gitmessage}")
+            CommitMessage::parse(&gitmessage, Some(&comment)).trailer_lines()
+        };
+
+        if trailers.is_empty() {
+            Ok(git_user)
+        } else {
+            Ok(format!("{git_user}\n{}", trailers.join("\n")))
         }
     }
 
-    pub fn get_all_coauthors(&self) -> LinkedHashMap<String, Author> {
-        let coauthors_path = self.get_coauthors_path();
-        let coauthors_path = coauthors_path.as_path();
-        let coauthors_str = self
-            .file_actions
-            .read(coauthors_path)
-            .unwrap_or_else(|_| String::from(""));
+    /// Returns the user's co-authors merged with the repository's trusted `.git-coauthors`
+    pub fn get_all_coauthors(&self) -> Result<LinkedHashMap<String, Author>> {
+        Ok(self
+            .get_all_coauthors_with_source()?
+            .into_iter()
+            .map(|(initials, (author, _))| (initials, author))
+            .collect())
+    }
+}
 
-        if coauthors_str.is_empty() {
-            return LinkedHashMap::new();
-        }
+/// Returns the roster called `file_name` in `dir`, in whichever format exists
+fn find_roster(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    find_rosters(dir, file_name).into_iter().next()
+}
+
+/// Returns every roster called `file_name` in `dir`, the one that is used first
+fn find_rosters(dir: &Path, file_name: &str) -> Vec<PathBuf> {
+    [RosterFormat::Json, RosterFormat::Toml, RosterFormat::Yaml]
+        .iter()
+        .map(|format| dir.join(format!("{file_name}{}", format.extension())))
+        .chain([dir.join(format!("{file_name}.yml"))])
+        .filter(|path| path.exists())
+        .collect()
+}
 
-        let coauthors: Coauthors = serde_json::from_str(coauthors_str.as_str()).unwrap();
-        coauthors.coauthors
+/// Returns the `commit.template` value for a gitmessage file in a worktree's own config
+///
+/// In a regular repository this is relative (".git/.gitmessage") so the repository can be moved.
+/// Git resolves it from the root of the worktree, so it's absolute when `.git` is not the git
+/// directory, like in linked worktrees, submodules or with a separate git dir.
+fn get_template_value(work_dir: Option<&Path>, gitmessage_path: &Path) -> String {
+    let is_default_layout = work_dir.is_some_and(|work_dir| {
+        let git_dir = work_dir.join(".git");
+        git_dir.is_dir() && gitmessage_path == git_dir.join(".gitmessage")
+    });
+
+    if is_default_layout {
+        String::from(".git/.gitmessage")
+    } else {
+        to_config_path(gitmessage_path)
     }
 }
 
+/// Returns `path` as an absolute path that can be used as a git config value
+fn to_config_path(path: &Path) -> String {
+    let path = std::path::absolute(path).unwrap_or(path.to_path_buf());
+    // the common dir of a linked worktree is usually given as "<git dir>/../.."
+    let path = path
+        .components()
+        .fold(PathBuf::new(), |mut path, component| {
+            match component {
+                Component::ParentDir => {
+                    path.pop();
+                }
+                component => path.push(component),
+            }
+            path
+        });
+    // git config treats backslashes as escapes, and git accepts forward slashes on Windows
+    path.display().to_string().replace('\\', "/")
+}
+
 pub mod test_utils {
     use super::*;
+    use crate::history::CommitInfo;
+    use chrono::{DateTime, FixedOffset};
     use serde_json::json;
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
     use std::collections::HashMap;
 
     pub struct MockFileActions {
@@ -249,7 +523,7 @@ pub mod test_utils {
     }
 
     impl FileActions for MockFileActions {
-        fn write(&self, path: &Path, s: &str) -> Result<(), String> {
+        fn write(&self, path: &Path, s: &str) -> std::result::Result<(), String> {
             println!("saving to test map {}", path.display());
             self.s
                 .borrow_mut()
@@ -257,29 +531,81 @@ pub mod test_utils {
             Ok(())
         }
 
-        fn read(&self, path: &Path) -> Result<String, String> {
+        fn read(&self, path: &Path) -> std::result::Result<String, String> {
             let key = path.display().to_string();
             match self.s.borrow().get(&key) {
                 Some(s) => Ok(s.to_string()),
-                None => panic!("!!! TEST SETUP ERROR: {} not found in map", key),
+                None => Err(format!("{} not found in test map", key)),
             }
         }
+
+        fn remove(&self, path: &Path) -> std::result::Result<(), String> {
+            self.s.borrow_mut().remove(&path.display().to_string());
+            Ok(())
+        }
     }
 
-    pub struct MockExitWithError {}
+    pub struct MockClock {
+        now: Cell<DateTime<FixedOffset>>,
+    }
 
-    impl ExitWithError for MockExitWithError {
-        fn message(&self, message: &str) -> ! {
-            panic!("{}", message);
+    impl MockClock {
+        /// Sets the current time from an RFC 3339 timestamp
+        pub fn set(&self, now: &str) {
+            self.now.set(DateTime::parse_from_rfc3339(now).unwrap());
         }
     }
 
-    pub fn get_git_mob() -> GitMob<MockFileActions, MockExitWithError> {
+    impl Clock for MockClock {
+        fn now(&self) -> DateTime<FixedOffset> {
+            self.now.get()
+        }
+    }
+
+    /// When the mock clock starts, and when test commits are made
+    pub const NOW: &str = "2024-01-01T09:00:00+00:00";
+
+    pub fn author(name: &str, email: &str) -> Author {
+        Author {
+            name: String::from(name),
+            email: String::from(email),
+        }
+    }
+
+    pub fn commit(id: &str, author: Author, message: &str) -> CommitInfo {
+        CommitInfo {
+            id: String::from(id),
+            author,
+            time: DateTime::parse_from_rfc3339(NOW).unwrap(),
+            message: String::from(message),
+        }
+    }
+
+    /// Runs git in `dir` as "A B <ab@example.com>", returning what it printed
+    pub fn git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=A B", "-c", "user.email=ab@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim_end()
+            .to_string()
+    }
+
+    pub fn get_git_mob() -> GitMob<MockFileActions, MockClock> {
         let gm = GitMob {
             file_actions: MockFileActions {
                 s: RefCell::new(HashMap::new()),
             },
-            exit_with_error: MockExitWithError {},
+            clock: MockClock {
+                now: Cell::new(DateTime::parse_from_rfc3339(NOW).unwrap()),
+            },
         };
 
         // set up
@@ -297,7 +623,7 @@ pub mod test_utils {
         });
 
         gm.file_actions
-            .write(&gm.get_coauthors_path(), &coauthors.to_string())
+            .write(&gm.get_coauthors_path().unwrap(), &coauthors.to_string())
             .unwrap();
 
         gm
@@ -306,11 +632,11 @@ pub mod test_utils {
 
 #[cfg(test)]
 mod test {
-    use std::{fs, io::Write};
+    use std::{fs, fs::File, io::Write};
 
     use super::*;
     use tempfile::tempdir;
-    use test_utils::get_git_mob;
+    use test_utils::{get_git_mob, git};
 
     #[test]
     fn test_write_gitmessage() {
@@ -318,13 +644,14 @@ mod test {
 
         let authors = "Co-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>";
 
-        gm.write_gitmessage(&[String::from("ab"), String::from("cd")]);
+        gm.write_gitmessage(&[String::from("ab"), String::from("cd")])
+            .unwrap();
 
-        assert_eq!(format!("\n\n{}", authors), gm.get_gitmessage());
-        assert_eq!("ab,cd\n", gm.get_gitinitials());
+        assert_eq!(format!("\n\n{}", authors), gm.get_gitmessage().unwrap());
+        assert_eq!("ab,cd\n", gm.get_gitinitials().unwrap());
         assert_eq!(
-            format!("{}\n{}", gm.get_git_user(), authors),
-            gm.get_formatted_gitmessage()
+            format!("{}\n{}", gm.get_git_user().unwrap(), authors),
+            gm.get_formatted_gitmessage().unwrap()
         );
     }
 
@@ -348,12 +675,27 @@ mod test {
             },
         );
 
-        assert_eq!(expected_coauthors, gm.get_all_coauthors());
+        assert_eq!(expected_coauthors, gm.get_all_coauthors().unwrap());
 
         // test empty
-        gm.file_actions.write(&gm.get_coauthors_path(), "").unwrap();
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), "")
+            .unwrap();
 
-        assert_eq!(LinkedHashMap::new(), gm.get_all_coauthors());
+        assert_eq!(LinkedHashMap::new(), gm.get_all_coauthors().unwrap());
+    }
+
+    #[test]
+    fn test_get_all_coauthors_bad_roster() {
+        let gm = get_git_mob();
+
+        gm.file_actions
+            .write(&gm.get_coauthors_path().unwrap(), "{\"coauthors\": {")
+            .unwrap();
+
+        let error = gm.get_all_coauthors().unwrap_err();
+        assert!(matches!(error, GitMobError::BadRoster { .. }));
+        assert_eq!(5, error.exit_code());
     }
 
     #[test]
@@ -385,12 +727,101 @@ mod test {
         }
 
         let gm = get_git_mob();
-        gm.set_git_template_config(&config_file_path);
+        gm.set_git_template_config(&config_file_path, Source::Local, ".git/.gitmessage")
+            .unwrap();
 
         let actual_config = fs::read_to_string(config_file_path).unwrap();
         assert_eq!(expected_config, actual_config);
     }
 
+    #[test]
+    fn test_get_template_value() {
+        let dir = tempdir().unwrap();
+        let work_dir = dir.path().join("repo");
+        let git_dir = work_dir.join(".git");
+        fs::create_dir_all(&git_dir).unwrap();
+
+        assert_eq!(
+            ".git/.gitmessage",
+            get_template_value(Some(&work_dir), &git_dir.join(".gitmessage"))
+        );
+
+        // a linked worktree, where .git is a file pointing to the git dir
+        let worktree = dir.path().join("worktree");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            "gitdir: ../repo/.git/worktrees/worktree",
+        )
+        .unwrap();
+
+        assert_eq!(
+            git_dir.join(".gitmessage").display().to_string(),
+            get_template_value(
+                Some(&worktree),
+                &git_dir.join("worktrees/worktree/../../.gitmessage")
+            )
+        );
+    }
+
+    #[test]
+    fn test_shared_template_in_linked_worktree() {
+        let dir = tempdir().unwrap();
+        let main = dir.path().join("main");
+        let linked = dir.path().join("linked");
+        git(dir.path(), &["init", "-q", "main"]);
+        git(&main, &["commit", "-q", "--allow-empty", "-m", "Start"]);
+        git(
+            &main,
+            &["worktree", "add", "-q", &linked.display().to_string()],
+        );
+
+        // set the mob from the main worktree
+        let gm = get_git_mob();
+        let repo = gix::open(&main).unwrap();
+        gm.set_git_template_config(
+            &repo.common_dir().join("config"),
+            Source::Local,
+            &gm.get_template_config_value(&repo),
+        )
+        .unwrap();
+        let gitmessage_path = repo.common_dir().join(GITMESSAGE);
+        fs::write(
+            &gitmessage_path,
+            "\n\nCo-authored-by: C D <cd@example.com>\n",
+        )
+        .unwrap();
+
+        // git resolves a relative template from the root of the worktree
+        let template = git(&linked, &["config", "commit.template"]);
+        let template = linked.join(template.trim());
+        assert!(template.is_file());
+        assert_eq!(
+            fs::canonicalize(gitmessage_path).unwrap(),
+            fs::canonicalize(template).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_set_git_config_creates_file() {
+        let dir = tempdir().unwrap();
+        let config_file_path = dir.path().join("config.worktree");
+
+        git_config::set_value(
+            &config_file_path,
+            Source::Worktree,
+            "commit.template",
+            "/repo/.git/worktrees/worktree/.gitmessage",
+        )
+        .unwrap();
+
+        let actual_config = fs::read_to_string(config_file_path).unwrap();
+        assert_eq!(
+            "[commit]\n\ttemplate = /repo/.git/worktrees/worktree/.gitmessage\n",
+            actual_config
+        );
+    }
+
     #[test]
     fn test_replace_git_template_config() {
         let default_config = "
@@ -410,6 +841,8 @@ mod test {
 \tlogallrefupdates = true
 [commit]
 \ttemplate = .git/.gitmessage
+[mob]
+\tpreviousTemplate = .git/.somethingelse
 ";
 
         let dir = tempdir().unwrap();
@@ -420,9 +853,15 @@ mod test {
         }
 
         let gm = get_git_mob();
-        gm.set_git_template_config(&config_file_path);
+        gm.set_git_template_config(&config_file_path, Source::Local, ".git/.gitmessage")
+            .unwrap();
 
-        let actual_config = fs::read_to_string(config_file_path).unwrap();
+        let actual_config = fs::read_to_string(&config_file_path).unwrap();
         assert_eq!(expected_config, actual_config);
+
+        // the replaced template is restored when going solo
+        assert!(template::restore_git_template_config(&config_file_path, Source::Local).unwrap());
+        let actual_config = fs::read_to_string(&config_file_path).unwrap();
+        assert_eq!(default_config, actual_config);
     }
 }
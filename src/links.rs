@@ -0,0 +1,123 @@
+use crate::completions::COMPLETE_VAR;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The original binaries, and "git-coauthors", which are links to "git-mob", and the subcommand
+/// each one runs
+pub const LINKS: &[(&str, &[&str])] = &[
+    ("git-solo", &["solo"]),
+    ("git-mob-print", &["print"]),
+    ("git-add-coauthor", &["coauthor", "add"]),
+    ("git-delete-coauthor", &["coauthor", "delete"]),
+    ("git-edit-coauthor", &["coauthor", "edit"]),
+    ("git-edit-coauthors", &["coauthor", "open"]),
+    ("git-coauthors", &["coauthor"]),
+];
+
+/// Returns the subcommand to run for the program `name`, such as ["solo"] for "git-solo"
+fn get_subcommand(name: &OsString) -> Option<&'static [&'static str]> {
+    let name = Path::new(name).file_stem()?;
+    LINKS
+        .iter()
+        .find(|(link, _)| name == *link)
+        .map(|(_, subcommand)| *subcommand)
+}
+
+/// Rewrites the arguments of a link like "git-solo -g" to "git-mob solo -g"
+///
+/// When the shell asks for completions, the command line being completed follows "--", so that
+/// is rewritten instead, along with the index of the word being completed.
+pub fn dispatch_args(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let mut args: Vec<OsString> = args.into_iter().collect();
+
+    let completing =
+        env::var_os(COMPLETE_VAR).is_some_and(|shell| !shell.is_empty() && shell != "0");
+    let program = if completing {
+        match args.iter().position(|arg| arg == "--") {
+            Some(escape) => escape + 1,
+            None => return args,
+        }
+    } else {
+        0
+    };
+
+    let Some(subcommand) = args.get(program).and_then(get_subcommand) else {
+        return args;
+    };
+
+    args.splice(
+        program + 1..program + 1,
+        subcommand.iter().map(OsString::from),
+    );
+    if completing {
+        if let Some(index) = env::var("_CLAP_COMPLETE_INDEX")
+            .ok()
+            .and_then(|index| index.parse::<usize>().ok())
+        {
+            env::set_var(
+                "_CLAP_COMPLETE_INDEX",
+                (index + subcommand.len()).to_string(),
+            );
+        }
+    } else {
+        args[program] = OsString::from("git-mob");
+    }
+    args
+}
+
+/// Links every original binary to the running "git-mob" in `dir`, replacing any old binaries
+///
+/// These are symbolic links, except on Windows where they're hard links so no extra privileges
+/// are needed.
+pub fn install_links(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let target = env::current_exe()?;
+
+    let mut links = vec![];
+    for (link, _) in LINKS {
+        let path = dir.join(format!("{link}{}", env::consts::EXE_SUFFIX));
+        if path.symlink_metadata().is_ok() {
+            fs::remove_file(&path)?;
+        }
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, &path)?;
+        #[cfg(not(unix))]
+        fs::hard_link(&target, &path)?;
+
+        links.push(path);
+    }
+    Ok(links)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn test_dispatch_args() {
+        assert_eq!(
+            to_args(&["git-mob", "solo", "-g"]),
+            dispatch_args(to_args(&["/usr/bin/git-solo", "-g"]))
+        );
+        assert_eq!(
+            to_args(&["git-mob", "coauthor", "add", "ab", "A B", "ab@example.com"]),
+            dispatch_args(to_args(&[
+                "git-add-coauthor",
+                "ab",
+                "A B",
+                "ab@example.com"
+            ]))
+        );
+        assert_eq!(
+            to_args(&["/usr/bin/git-mob", "ab"]),
+            dispatch_args(to_args(&["/usr/bin/git-mob", "ab"]))
+        );
+    }
+}
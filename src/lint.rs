@@ -0,0 +1,364 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::roster::RosterFormat;
+use crate::GitMob;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// The JSON schema of the coauthors file, which editors use to validate and autocomplete it
+pub const SCHEMA: &str = include_str!("../git-coauthors.schema.json");
+
+/// Where the schema is published, for the `$schema` key of a coauthors file
+pub const SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/jplsek/git-mob-rs/main/git-coauthors.schema.json";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The file can't be used as is
+    Error,
+    /// The file works, but something looks off
+    Warning,
+}
+
+/// A problem found in a coauthors file
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct LintIssue {
+    pub severity: Severity,
+    /// The line and column, starting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initials: Option<String>,
+    pub message: String,
+}
+
+/// The problems found in one coauthors file
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct LintReport {
+    pub file: PathBuf,
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+    }
+}
+
+/// Returns whether an email looks like "local@domain.tld", without spaces or angle brackets
+/// that would break the trailer
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain
+            .split_once('.')
+            .is_some_and(|(host, tld)| !host.is_empty() && !tld.is_empty() && !tld.ends_with('.'))
+        && !email
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '<' | '>' | ','))
+}
+
+/// Returns the line, starting from 1, where `key` is defined in the `table` section, such as the
+/// co-author with some initials in "coauthors"
+fn find_key_line(content: &str, table: &str, key: &str) -> Option<usize> {
+    let headers = [
+        format!("\"{table}\""),
+        format!("'{table}'"),
+        format!("{table}:"),
+        format!("[{table}]"),
+        format!("[{table}."),
+    ];
+    let keys = [
+        format!("\"{key}\""),
+        format!("'{key}'"),
+        format!("{key}:"),
+        format!("{key} ="),
+        format!("{key}="),
+        format!("[{table}.{key}]"),
+        format!("[{table}.\"{key}\"]"),
+    ];
+    let starts_with_any =
+        |line: &str, prefixes: &[String]| prefixes.iter().any(|p| line.trim_start().starts_with(p));
+
+    // so a group isn't found at the co-author with the same initials
+    let start = content
+        .lines()
+        .position(|line| starts_with_any(line, &headers))
+        .unwrap_or_default();
+    content
+        .lines()
+        .enumerate()
+        .skip(start)
+        .find(|(_, line)| starts_with_any(line, &keys))
+        .map(|(index, _)| index + 1)
+}
+
+/// Lints the content of a coauthors file
+///
+/// With `seen_emails`, the lowercased emails of the repository's authors and co-authors, it also
+/// warns about co-authors who never worked on the repository.
+pub fn lint_roster(
+    path: &Path,
+    content: &str,
+    seen_emails: Option<&HashSet<String>>,
+) -> Vec<LintIssue> {
+    let roster = match RosterFormat::detect(path, content).parse(content) {
+        Ok(roster) => roster,
+        Err(error) => {
+            return vec![LintIssue {
+                severity: Severity::Error,
+                line: error.location.map(|(line, _)| line),
+                column: error.location.map(|(_, column)| column),
+                initials: None,
+                message: error.message,
+            }]
+        }
+    };
+
+    let mut issues = vec![];
+    let mut emails: HashMap<String, &str> = HashMap::new();
+    for (initials, author) in &roster.coauthors {
+        let mut issue = |severity, message| {
+            issues.push(LintIssue {
+                severity,
+                line: find_key_line(content, "coauthors", initials),
+                column: None,
+                initials: Some(initials.to_string()),
+                message,
+            })
+        };
+
+        if initials.is_empty() || initials.chars().any(char::is_whitespace) {
+            issue(
+                Severity::Error,
+                format!("the initials \"{initials}\" should not be empty or contain whitespace"),
+            );
+        }
+        if author.name.trim().is_empty() {
+            issue(Severity::Error, String::from("the name is empty"));
+        }
+        if !is_valid_email(&author.email) {
+            issue(
+                Severity::Error,
+                format!("\"{}\" is not a valid email", author.email),
+            );
+        }
+
+        let email = author.email.to_lowercase();
+        match emails.get(email.as_str()) {
+            Some(other) => issue(
+                Severity::Error,
+                format!("{} is also the email of \"{other}\"", author.email),
+            ),
+            None => {
+                emails.insert(email.clone(), initials);
+            }
+        }
+
+        if seen_emails.is_some_and(|seen_emails| !seen_emails.contains(&email)) {
+            issue(
+                Severity::Warning,
+                format!(
+                    "{} has never authored or co-authored a commit in this repository",
+                    author.email
+                ),
+            );
+        }
+    }
+
+    for (name, members) in &roster.groups {
+        let mut issue = |severity, message| {
+            issues.push(LintIssue {
+                severity,
+                line: find_key_line(content, "groups", name),
+                column: None,
+                initials: Some(name.to_string()),
+                message,
+            })
+        };
+
+        if name.is_empty() || name.chars().any(char::is_whitespace) {
+            issue(
+                Severity::Error,
+                format!("the group name \"{name}\" should not be empty or contain whitespace"),
+            );
+        }
+        if roster.coauthors.contains_key(name) {
+            issue(
+                Severity::Warning,
+                format!("the group is hidden by the co-author with the initials \"{name}\""),
+            );
+        }
+        if members.is_empty() {
+            issue(Severity::Warning, String::from("the group is empty"));
+        }
+        // members may be in the other coauthors file
+        for member in members {
+            if !roster.coauthors.contains_key(member) {
+                issue(
+                    Severity::Warning,
+                    format!("\"{member}\" is not a co-author in this file"),
+                );
+            }
+        }
+    }
+    issues
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the lowercased emails of everyone who authored or co-authored a commit in the
+    /// repository, if it has any commits
+    fn get_seen_emails(&self) -> Option<HashSet<String>> {
+        let commits = self.get_commits("HEAD", None, None).ok()?;
+        Some(
+            commits
+                .iter()
+                .flat_map(|commit| {
+                    let mut people = commit.coauthors();
+                    people.push(commit.author.clone());
+                    people
+                })
+                .map(|person| person.email.to_lowercase())
+                .collect(),
+        )
+    }
+
+    /// Lints a coauthors file, or by default the user's and the repository's
+    pub fn lint_coauthors(&self, path: Option<&Path>) -> Result<Vec<LintReport>> {
+        let paths = match path {
+            Some(path) => vec![path.to_path_buf()],
+            None => vec![
+                Some(self.get_coauthors_path()?),
+                self.get_repo_coauthors_path()?,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|path| self.file_actions.read(path).is_ok())
+            .collect(),
+        };
+
+        let seen_emails = self.get_seen_emails();
+        paths
+            .into_iter()
+            .map(|path| {
+                let content = self.file_actions.read(&path).map_err(GitMobError::Io)?;
+                let issues = lint_roster(&path, &content, seen_emails.as_ref());
+                Ok(LintReport {
+                    file: std::path::absolute(&path).unwrap_or(path),
+                    issues,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_email() {
+        assert!(is_valid_email("ab@example.com"));
+        assert!(is_valid_email("a.b+mob@mail.example.co.uk"));
+        assert!(!is_valid_email("ab"));
+        assert!(!is_valid_email("ab@"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("ab@localhost"));
+        assert!(!is_valid_email("ab@example."));
+        assert!(!is_valid_email("a b@example.com"));
+        assert!(!is_valid_email("<ab@example.com>"));
+    }
+
+    #[test]
+    fn test_lint_roster() {
+        let content = r#"{
+  "coauthors": {
+    "ab": {
+      "name": "A B",
+      "email": "ab@example.com"
+    },
+    "c d": {
+      "name": " ",
+      "email": "cd@example"
+    },
+    "ab2": {
+      "name": "A B",
+      "email": "AB@example.com"
+    }
+  }
+}"#;
+        let seen_emails = HashSet::from([String::from("ab@example.com")]);
+
+        let issues = lint_roster(Path::new("git-coauthors"), content, Some(&seen_emails));
+        let issues: Vec<(Severity, Option<usize>, Option<&str>)> = issues
+            .iter()
+            .map(|issue| (issue.severity, issue.line, issue.initials.as_deref()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Severity::Error, Some(7), Some("c d")),
+                (Severity::Error, Some(7), Some("c d")),
+                (Severity::Error, Some(7), Some("c d")),
+                (Severity::Warning, Some(7), Some("c d")),
+                (Severity::Error, Some(11), Some("ab2")),
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn test_lint_roster_groups() {
+        let content = "[coauthors.ab]
+name = \"A B\"
+email = \"ab@example.com\"
+
+[groups]
+core = [\"ab\", \"zz\"]
+ab = [\"ab\"]
+\"t 1\" = []
+";
+
+        let issues = lint_roster(Path::new("git-coauthors.toml"), content, None);
+        let issues: Vec<(Severity, Option<usize>, Option<&str>)> = issues
+            .iter()
+            .map(|issue| (issue.severity, issue.line, issue.initials.as_deref()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Severity::Warning, Some(6), Some("core")),
+                (Severity::Warning, Some(7), Some("ab")),
+                (Severity::Error, Some(8), Some("t 1")),
+                (Severity::Warning, Some(8), Some("t 1")),
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn test_lint_roster_parse_error() {
+        let issues = lint_roster(
+            Path::new("git-coauthors.toml"),
+            "[coauthors.ab]\nname = \"A B\"\nemail = ab@example.com\n",
+            None,
+        );
+
+        assert_eq!(1, issues.len());
+        assert_eq!(Severity::Error, issues[0].severity);
+        assert_eq!(Some(3), issues[0].line);
+        assert_eq!(Some(9), issues[0].column);
+
+        let issues = lint_roster(Path::new("git-coauthors"), "{\"coauthors\": {,}}", None);
+        assert_eq!(Some(1), issues[0].line);
+        assert_eq!(Some(16), issues[0].column);
+    }
+}
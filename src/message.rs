@@ -0,0 +1,588 @@
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::file_actions::FileActions;
+use crate::trailers::DEFAULT_TRAILER_KEY;
+use crate::{Author, GitMob};
+use std::fmt;
+use std::ops::Range;
+
+pub const DEFAULT_COMMENT_PREFIX: &str = "#";
+
+// Follows the comment prefix on the line git cuts the message at
+const SCISSORS: &str = " ------------------------ >8 ------------------------";
+
+// Lines git adds itself, which make a paragraph a trailer block even with other lines in it
+const GIT_GENERATED_PREFIXES: [&str; 2] = ["Signed-off-by: ", "(cherry picked from commit "];
+
+// Trailers that conventionally end the block: a sign-off certifies everything above it, and
+// Gerrit wants the Change-Id last
+const CLOSING_KEYS: [&str; 2] = ["Signed-off-by", "Change-Id"];
+
+/// A trailer such as "Co-authored-by: A B <ab@example.com>"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    pub key: String,
+    /// The value, with any continuation lines joined by spaces
+    pub value: String,
+}
+
+impl Trailer {
+    /// Parses a trailer line, a key of letters, digits and dashes followed by a colon
+    ///
+    /// Like git, whitespace is allowed between the key and the colon.
+    pub fn parse(line: &str) -> Option<Trailer> {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim_end();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+        Some(Trailer {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+
+    /// Returns whether the trailer has the key, which git matches ignoring case
+    pub fn has_key(&self, key: &str) -> bool {
+        self.key.eq_ignore_ascii_case(key)
+    }
+
+    /// Returns whether both trailers say the same, ignoring case
+    fn matches(&self, other: &Trailer) -> bool {
+        self.has_key(&other.key) && self.value.to_lowercase() == other.value.to_lowercase()
+    }
+
+    /// Returns the person in a "Name <email>" value, if it has an email
+    pub fn author(&self) -> Option<Author> {
+        let (name, email) = self.value.rsplit_once('<')?;
+        let email = email.strip_suffix('>')?.trim();
+        if email.is_empty() {
+            return None;
+        }
+        Some(Author {
+            name: name.trim().to_string(),
+            email: email.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
+    }
+}
+
+/// A trailer the mob requires, and the email of who it credits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequiredTrailer {
+    pub email: String,
+    pub trailer: String,
+}
+
+/// A commit message split into its title, body, trailer block and the comments git adds at
+/// the end, following the rules of `git interpret-trailers`
+///
+/// The trailer block is the last paragraph, if it's all trailers, or at least a quarter of it
+/// is with a line git generates such as "Signed-off-by". The title, the first paragraph, is never
+/// a trailer block. With comments, the message ends at the scissors line "git commit -v" puts
+/// above the diff.
+#[derive(Debug)]
+pub struct CommitMessage<'a> {
+    message: &'a str,
+    lines: Vec<&'a str>,
+    /// What starts a comment line, when the message still has git's comments
+    comment: Option<&'a str>,
+    /// Where the title paragraph ends
+    title_end: usize,
+    /// The lines of the trailer block, if there is one
+    block: Option<Range<usize>>,
+    /// Where the trailing comments and blank lines start
+    content_end: usize,
+}
+
+impl<'a> CommitMessage<'a> {
+    /// Parses a message, ignoring lines that start with `comment` like git does before
+    /// committing
+    ///
+    /// Messages read from commits have had their comments removed, so they're parsed without.
+    pub fn parse(message: &'a str, comment: Option<&'a str>) -> Self {
+        let lines: Vec<&str> = message.lines().collect();
+        let is_comment = |line: &str| comment.is_some_and(|comment| line.starts_with(comment));
+
+        // "git commit -v" puts the diff below a scissors line, and everything from it on is
+        // dropped from the message
+        let scissors = comment.map(|comment| format!("{comment}{SCISSORS}"));
+        let mut content_end = lines
+            .iter()
+            .position(|line| scissors.as_deref() == Some(*line))
+            .unwrap_or(lines.len());
+        while content_end > 0
+            && (lines[content_end - 1].trim().is_empty() || is_comment(lines[content_end - 1]))
+        {
+            content_end -= 1;
+        }
+
+        let title_end = (0..content_end)
+            .find(|&i| !is_comment(lines[i]) && lines[i].trim().is_empty())
+            .unwrap_or(content_end);
+        let block = (title_end..content_end)
+            .rev()
+            .find(|&i| lines[i].trim().is_empty())
+            .map(|blank| blank + 1..content_end)
+            .filter(|block| is_trailer_block(&lines[block.clone()], is_comment));
+
+        CommitMessage {
+            message,
+            lines,
+            comment,
+            title_end,
+            block,
+            content_end,
+        }
+    }
+
+    fn is_comment(&self, line: &str) -> bool {
+        self.comment
+            .is_some_and(|comment| line.starts_with(comment))
+    }
+
+    /// Returns the non-comment lines in a range
+    fn content(&self, range: Range<usize>) -> impl Iterator<Item = &'a str> + '_ {
+        self.lines[range]
+            .iter()
+            .copied()
+            .filter(move |line| !self.is_comment(line))
+    }
+
+    /// Returns the title, the first paragraph joined into one line
+    pub fn title(&self) -> String {
+        self.content(0..self.title_end)
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the paragraphs between the title and the trailer block
+    pub fn body(&self) -> String {
+        let end = self
+            .block
+            .as_ref()
+            .map_or(self.content_end, |block| block.start);
+        let start = self.title_end.min(end);
+        self.content(start..end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_matches('\n')
+            .to_string()
+    }
+
+    /// Returns the lines of the trailer block as they are, without comments
+    pub fn trailer_lines(&self) -> Vec<&'a str> {
+        match &self.block {
+            Some(block) => self.content(block.clone()).collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns the trailers in the trailer block, in order
+    pub fn trailers(&self) -> Vec<Trailer> {
+        let mut trailers: Vec<Trailer> = vec![];
+        let mut continues = false;
+        for line in self.trailer_lines() {
+            if continues && line.starts_with(char::is_whitespace) {
+                if let Some(trailer) = trailers.last_mut() {
+                    trailer.value = format!("{} {}", trailer.value, line.trim());
+                }
+                continue;
+            }
+            let trailer = Trailer::parse(line);
+            continues = trailer.is_some();
+            trailers.extend(trailer);
+        }
+        trailers
+    }
+
+    /// Returns the people in the `Co-authored-by` trailers, in order
+    pub fn coauthors(&self) -> Vec<Author> {
+        self.trailers()
+            .iter()
+            .filter(|trailer| trailer.has_key(DEFAULT_TRAILER_KEY))
+            .filter_map(Trailer::author)
+            .collect()
+    }
+
+    /// Returns the trailers that aren't in the trailer block yet, ignoring case
+    pub fn missing<'t>(&self, trailers: &'t [String]) -> Vec<&'t String> {
+        let existing = self.trailers();
+        trailers
+            .iter()
+            .filter(|line| match Trailer::parse(line) {
+                Some(trailer) => !existing.iter().any(|existing| existing.matches(&trailer)),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Returns the required trailers that don't credit anyone in the trailer block yet, leaving
+    /// out the commit's author
+    ///
+    /// Someone is credited by a trailer with the same key and their email, whatever their name
+    /// is spelled like, or by the same trailer ignoring case.
+    pub(crate) fn missing_required<'r>(
+        &self,
+        required: &'r [RequiredTrailer],
+        author_email: Option<&str>,
+    ) -> Vec<&'r RequiredTrailer> {
+        let existing = self.trailers();
+        required
+            .iter()
+            .filter(|required| {
+                if author_email.is_some_and(|email| email.eq_ignore_ascii_case(&required.email)) {
+                    return false;
+                }
+                let Some(trailer) = Trailer::parse(&required.trailer) else {
+                    return true;
+                };
+                !existing.iter().any(|existing| {
+                    existing.matches(&trailer)
+                        || (existing.has_key(&trailer.key)
+                            && existing.author().is_some_and(|author| {
+                                author.email.eq_ignore_ascii_case(&required.email)
+                            }))
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the message with the trailers that are missing added to its trailer block, or
+    /// to a new one, keeping git's comments at the end
+    ///
+    /// A trailer goes after the last one with the same key, or else before any "Signed-off-by"
+    /// and "Change-Id", so those stay last.
+    pub fn merge(&self, trailers: &[String]) -> String {
+        let missing = self.missing(trailers);
+        if missing.is_empty() {
+            return self.message.to_string();
+        }
+
+        let mut content = self.lines[..self.content_end].to_vec();
+        match &self.block {
+            Some(block) => {
+                let mut block = block.clone();
+                for trailer in missing {
+                    let key = Trailer::parse(trailer).map(|trailer| trailer.key);
+                    let at = self.insert_position(&content, block.clone(), key.as_deref());
+                    content.insert(at, trailer);
+                    block.end += 1;
+                }
+            }
+            None => {
+                // an empty message leaves room for the title, like the .gitmessage template
+                if content.is_empty() {
+                    content.push("");
+                }
+                content.push("");
+                content.extend(missing.iter().map(|trailer| trailer.as_str()));
+            }
+        }
+        content.extend(&self.lines[self.content_end..]);
+
+        content.iter().map(|line| format!("{line}\n")).collect()
+    }
+
+    /// Returns where a trailer with the key goes in the block
+    fn insert_position(&self, lines: &[&str], block: Range<usize>, key: Option<&str>) -> usize {
+        let mut after_same_key = None;
+        let mut before_closing = None;
+        let mut current = None;
+        for i in block.clone() {
+            let line = lines[i];
+            if self.is_comment(line) {
+                continue;
+            }
+            if !line.starts_with(char::is_whitespace) {
+                current = Trailer::parse(line);
+                let is_closing = current.as_ref().is_some_and(|trailer| {
+                    CLOSING_KEYS.iter().any(|closing| trailer.has_key(closing))
+                });
+                if is_closing && before_closing.is_none() {
+                    before_closing = Some(i);
+                }
+            }
+            let same_key = current
+                .as_ref()
+                .zip(key)
+                .is_some_and(|(trailer, key)| trailer.has_key(key));
+            if same_key {
+                after_same_key = Some(i + 1);
+            }
+        }
+        after_same_key.or(before_closing).unwrap_or(block.end)
+    }
+}
+
+fn is_trailer_block(lines: &[&str], is_comment: impl Fn(&str) -> bool) -> bool {
+    let mut trailers = 0;
+    let mut others = 0;
+    let mut git_generated = false;
+    let mut continues = false;
+    for line in lines {
+        if is_comment(line) || (continues && line.starts_with(char::is_whitespace)) {
+            continue;
+        }
+        if GIT_GENERATED_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            git_generated = true;
+            trailers += 1;
+            continues = true;
+        } else if Trailer::parse(line).is_some() {
+            trailers += 1;
+            continues = true;
+        } else {
+            others += 1;
+            continues = false;
+        }
+    }
+    trailers > 0 && (others == 0 || (git_generated && trailers * 3 >= others))
+}
+
+/// Returns the people in the `Co-authored-by` trailers of a commit's message, in order
+///
+/// The trailer key is matched case-insensitively, as git does.
+pub fn parse_coauthors(message: &str) -> Vec<Author> {
+    CommitMessage::parse(message, None).coauthors()
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns what starts a comment line in commit messages, set with `core.commentString` or
+    /// `core.commentChar`
+    ///
+    /// With "auto", git picks a character the message doesn't use, which can't be known here,
+    /// so the default "#" is assumed.
+    pub fn get_comment_prefix(&self) -> Result<String> {
+        let prefix = match self.get_git_config_value("core.commentString")? {
+            Some(prefix) => Some(prefix),
+            None => self.get_git_config_value("core.commentChar")?,
+        };
+        Ok(match prefix {
+            Some(prefix) if !prefix.is_empty() && prefix != "auto" => prefix,
+            _ => String::from(DEFAULT_COMMENT_PREFIX),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::author;
+
+    fn trailers(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let message = CommitMessage::parse(
+            "Add a feature
+that wraps
+
+Some details: with a colon
+
+More details
+Co-authored-by: not a trailer block
+
+Co-authored-by: A B <ab@example.com>
+Reviewed-by : C D
+  <cd@example.com>
+# Please enter the commit message
+",
+            Some("#"),
+        );
+
+        assert_eq!("Add a feature that wraps", message.title());
+        assert_eq!(
+            "Some details: with a colon\n\nMore details\nCo-authored-by: not a trailer block",
+            message.body()
+        );
+        assert_eq!(
+            vec![
+                Trailer {
+                    key: String::from("Co-authored-by"),
+                    value: String::from("A B <ab@example.com>"),
+                },
+                Trailer {
+                    key: String::from("Reviewed-by"),
+                    value: String::from("C D <cd@example.com>"),
+                },
+            ],
+            message.trailers()
+        );
+
+        // the title is never trailers
+        let message = CommitMessage::parse("Fix: the thing\n", None);
+        assert!(message.trailers().is_empty());
+        assert_eq!("Fix: the thing", message.title());
+
+        // a few other lines are fine with a git-generated trailer
+        let message = CommitMessage::parse(
+            "Fix\n\nSee the issue\nSigned-off-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>\n",
+            None,
+        );
+        assert_eq!(2, message.trailers().len());
+        assert_eq!("", message.body());
+
+        // comments are content in commits
+        let message = CommitMessage::parse("Fix\n\n#123: Reported-by: A B\n", None);
+        assert!(message.trailers().is_empty());
+        assert_eq!("#123: Reported-by: A B", message.body());
+    }
+
+    #[test]
+    fn test_parse_coauthors() {
+        let message = "Add a feature
+
+Some details: with a colon
+
+Co-authored-by: A B <ab@example.com>
+co-authored-by:C D <cd@example.com>
+Signed-off-by: E F <ef@example.com>
+Co-authored-by: no email
+Co-authored-by: G H <>
+";
+
+        assert_eq!(
+            vec![
+                author("A B", "ab@example.com"),
+                author("C D", "cd@example.com")
+            ],
+            parse_coauthors(message)
+        );
+        assert_eq!(Vec::<Author>::new(), parse_coauthors("Solo commit\n"));
+    }
+
+    #[test]
+    fn test_missing_required() {
+        let required = |initials: &str, email: &str| RequiredTrailer {
+            email: email.to_string(),
+            trailer: format!("Co-authored-by: {} <{email}>", initials.to_uppercase()),
+        };
+        let mob = [
+            required("ab", "ab@example.com"),
+            required("cd", "cd@example.com"),
+            required("ef", "ef@example.com"),
+        ];
+        let message = CommitMessage::parse(
+            "Fix it\n\nCo-authored-by: Cee Dee <CD@example.com>\nSigned-off-by: E F <ef@example.com>\n",
+            None,
+        );
+
+        // a co-author is credited by their email, and the author never has to be
+        assert_eq!(
+            vec![&mob[2]],
+            message.missing_required(&mob, Some("AB@example.com"))
+        );
+        assert_eq!(vec![&mob[0], &mob[2]], message.missing_required(&mob, None));
+
+        // a trailer without an email has to be there as it is
+        let mob = [RequiredTrailer {
+            email: String::from("ab@example.com"),
+            trailer: String::from("Pair: ab"),
+        }];
+        assert!(CommitMessage::parse("Fix it\n\npair: AB\n", None)
+            .missing_required(&mob, None)
+            .is_empty());
+        assert_eq!(
+            vec![&mob[0]],
+            CommitMessage::parse("Fix it\n\nPair: cd\n", None).missing_required(&mob, None)
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mob = trailers(&[
+            "Co-authored-by: A B <ab@example.com>",
+            "Co-authored-by: C D <cd@example.com>",
+        ]);
+        let merged = "Co-authored-by: A B <ab@example.com>\nCo-authored-by: C D <cd@example.com>\n";
+        let merge = |message: &str| CommitMessage::parse(message, Some("#")).merge(&mob);
+
+        // keeps git's comments at the end
+        assert_eq!(
+            format!("Subject\n\n{merged}\n# Please enter the commit message\n"),
+            merge("Subject\n\n# Please enter the commit message\n")
+        );
+
+        // continues an existing trailer block without duplicating, after the same key
+        assert_eq!(
+            "Subject\n\nco-authored-by: a b <AB@example.com>\nCo-authored-by: C D <cd@example.com>\nReviewed-by: E F <ef@example.com>\n",
+            merge("Subject\n\nco-authored-by: a b <AB@example.com>\nReviewed-by: E F <ef@example.com>\n")
+        );
+
+        // sign-offs and the Change-Id stay last
+        assert_eq!(
+            format!("Subject\n\nReviewed-by: E F <ef@example.com>\n{merged}Signed-off-by: E F <ef@example.com>\nChange-Id: I1234\n"),
+            merge("Subject\n\nReviewed-by: E F <ef@example.com>\nSigned-off-by: E F <ef@example.com>\nChange-Id: I1234\n")
+        );
+
+        // a paragraph that isn't trailers gets a new block after it
+        assert_eq!(
+            format!("Subject\n\nSee: the issue\nfor details\n\n{merged}"),
+            merge("Subject\n\nSee: the issue\nfor details\n")
+        );
+
+        // empty messages leave room for the subject
+        assert_eq!(format!("\n\n{merged}"), merge(""));
+
+        // nothing missing leaves the message as it is
+        let message = format!("Subject\n\n{merged}\n\n");
+        assert_eq!(message, merge(&message));
+    }
+
+    #[test]
+    fn test_merge_comment_prefix() {
+        let mob = trailers(&["Co-authored-by: A B <ab@example.com>"]);
+
+        assert_eq!(
+            "Subject\n\nCo-authored-by: A B <ab@example.com>\n\n; Please enter the commit message\n; # not a heading\n",
+            CommitMessage::parse(
+                "Subject\n\n; Please enter the commit message\n; # not a heading\n",
+                Some(";")
+            )
+            .merge(&mob)
+        );
+        assert_eq!(
+            "Subject\n\n#1 is fixed\n\nCo-authored-by: A B <ab@example.com>\n",
+            CommitMessage::parse("Subject\n\n#1 is fixed\n", Some(";")).merge(&mob)
+        );
+    }
+
+    #[test]
+    fn test_parse_scissors() {
+        let mob = trailers(&["Co-authored-by: A B <ab@example.com>"]);
+        let diff = "; ------------------------ >8 ------------------------
+; Do not modify or remove the line above.
+diff --git a/README.md b/README.md
+@@ -1 +1,2 @@
+ # README
++Foo: bar
+
+Co-authored-by: A B <ab@example.com>
+";
+        let message = format!("Subject\n\n; Please enter the commit message\n{diff}");
+
+        // nothing below the line is the trailer block, not even trailers
+        let parsed = CommitMessage::parse(&message, Some(";"));
+        assert!(parsed.trailers().is_empty());
+        assert_eq!("", parsed.body());
+        assert_eq!(vec![&mob[0]], parsed.missing(&mob));
+        assert_eq!(
+            format!("Subject\n\nCo-authored-by: A B <ab@example.com>\n\n; Please enter the commit message\n{diff}"),
+            parsed.merge(&mob)
+        );
+
+        // without comments, as in a commit, the line is content
+        assert_eq!(mob, CommitMessage::parse(&message, None).trailer_lines());
+    }
+}
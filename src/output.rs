@@ -0,0 +1,252 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::roster::RosterSource;
+use crate::{Author, GitMob, MobScope, GITINITIALS, GITMESSAGE};
+use clap::Args;
+use serde::Serialize;
+use serde_json::json;
+use std::path::PathBuf;
+
+/// The `--json` and `--quiet` flags shared by every command
+#[derive(Args, Debug, Default, Clone, Copy)]
+pub struct Output {
+    /// Print the result as JSON, for scripts and editor integrations
+    #[arg(long, global = true, conflicts_with = "quiet")]
+    pub json: bool,
+    /// Print nothing but errors
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+}
+
+impl Output {
+    /// Prints the result of `human` as is, or the result of `json` with `--json`
+    ///
+    /// Only one of them is called. With `--quiet`, `human` is still called for its errors but
+    /// nothing is printed.
+    pub fn print<S: Serialize>(
+        &self,
+        human: impl FnOnce() -> Result<String>,
+        json: impl FnOnce() -> Result<S>,
+    ) -> Result<()> {
+        if self.json {
+            // serializing these plain structs can't fail
+            println!("{}", serde_json::to_string_pretty(&json()?).unwrap());
+            return Ok(());
+        }
+
+        let human = human()?;
+        if !self.quiet {
+            print!("{human}");
+        }
+        Ok(())
+    }
+
+    /// Prints a warning to stderr, unless `--quiet`
+    pub fn warn(&self, warning: &str) {
+        if !self.quiet {
+            eprintln!("Warning: {warning}");
+        }
+    }
+
+    /// Prints the error to stderr, as JSON with `--json`, and exits with its exit code
+    pub fn exit(&self, error: GitMobError) -> ! {
+        if self.json {
+            eprintln!(
+                "{}",
+                json!({
+                    "error": {
+                        "code": error.exit_code(),
+                        "message": error.to_string(),
+                    }
+                })
+            );
+            std::process::exit(error.exit_code());
+        }
+        error.exit()
+    }
+}
+
+/// A co-author, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct CoauthorJson {
+    pub initials: String,
+    #[serde(flatten)]
+    pub author: Author,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<RosterSource>,
+}
+
+/// The mob that applies here, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct MobStatus {
+    /// The git user committing
+    pub user: Author,
+    /// Where the mob is set, if anywhere
+    pub scope: Option<MobScope>,
+    /// The rest of the mob, empty when solo
+    pub coauthors: Vec<CoauthorJson>,
+    /// The files the mob is kept in
+    pub files: Vec<PathBuf>,
+}
+
+/// A change to the user's coauthors file, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct RosterChange {
+    /// "added", "updated" or "removed"
+    pub result: &'static str,
+    pub coauthors: Vec<CoauthorJson>,
+    pub file: PathBuf,
+}
+
+/// A group of co-authors, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct GroupJson {
+    pub name: String,
+    /// The initials of its members
+    pub members: Vec<String>,
+}
+
+/// A change to the groups in the user's coauthors file, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct GroupChange {
+    /// "added", "updated" or "removed"
+    pub result: &'static str,
+    pub groups: Vec<GroupJson>,
+    pub file: PathBuf,
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the mob that applies here
+    pub fn get_mob_status(&self) -> Result<MobStatus> {
+        let user = Author {
+            name: self.get_git_config_value("user.name")?.unwrap_or_default(),
+            email: self.get_git_config_value("user.email")?.unwrap_or_default(),
+        };
+
+        let scope = self.get_active_scope()?;
+        let coauthors = self.get_all_coauthors()?;
+        let coauthors = self
+            .get_active_initials()?
+            .into_iter()
+            .filter_map(|initials| {
+                let author = coauthors.get(&initials)?.clone();
+                Some(CoauthorJson {
+                    initials,
+                    author,
+                    source: None,
+                })
+            })
+            .collect();
+
+        let files = match scope {
+            Some(scope) => vec![
+                self.get_scope_path(scope, GITMESSAGE)?,
+                self.get_scope_path(scope, GITINITIALS)?,
+            ],
+            None => vec![],
+        };
+        let files = files
+            .into_iter()
+            .map(|path| std::path::absolute(&path).unwrap_or(path))
+            .collect();
+
+        Ok(MobStatus {
+            user,
+            scope,
+            coauthors,
+            files,
+        })
+    }
+
+    /// Returns a change to the user's coauthors file, listing the co-authors with `initials`
+    /// that are in it now
+    pub fn get_roster_change(
+        &self,
+        result: &'static str,
+        initials: &[String],
+    ) -> Result<RosterChange> {
+        let coauthors = self.get_user_coauthors()?;
+        let coauthors = initials
+            .iter()
+            .filter_map(|initials| {
+                Some(CoauthorJson {
+                    initials: initials.to_string(),
+                    author: coauthors.get(initials)?.clone(),
+                    source: None,
+                })
+            })
+            .collect();
+
+        Ok(RosterChange {
+            result,
+            coauthors,
+            file: self.get_coauthors_path()?,
+        })
+    }
+
+    /// Returns a change to the groups in the user's coauthors file, listing the groups called
+    /// `names` that are in it now
+    pub fn get_group_change(&self, result: &'static str, names: &[String]) -> Result<GroupChange> {
+        let groups = self.get_user_groups()?;
+        let groups = names
+            .iter()
+            .filter_map(|name| {
+                Some(GroupJson {
+                    name: name.to_string(),
+                    members: groups.get(name)?.clone(),
+                })
+            })
+            .collect();
+
+        Ok(GroupChange {
+            result,
+            groups,
+            file: self.get_coauthors_path()?,
+        })
+    }
+
+    /// Returns every co-author along with the roster they come from
+    pub fn get_roster_json(&self) -> Result<Vec<CoauthorJson>> {
+        Ok(self
+            .get_all_coauthors_with_source()?
+            .into_iter()
+            .map(|(initials, (author, source))| CoauthorJson {
+                initials,
+                author,
+                source: Some(source),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+
+    #[test]
+    fn test_get_mob_status() {
+        let gm = get_git_mob();
+
+        gm.write_gitmessage(&[String::from("cd")]).unwrap();
+        let status = gm.get_mob_status().unwrap();
+
+        assert_eq!(Some(MobScope::Local), status.scope);
+        assert_eq!(
+            serde_json::json!([{
+                "initials": "cd",
+                "name": "C D",
+                "email": "cd@example.com"
+            }]),
+            serde_json::to_value(&status.coauthors).unwrap()
+        );
+        assert_eq!(
+            vec![
+                std::path::absolute(gm.get_gitmessage_path().unwrap()).unwrap(),
+                std::path::absolute(gm.get_gitinitials_path().unwrap()).unwrap()
+            ],
+            status.files
+        );
+    }
+}
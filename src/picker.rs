@@ -0,0 +1,575 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::suggest::SUGGEST_DEPTH;
+use crate::{Author, GitMob};
+use linked_hash_map::LinkedHashMap;
+use std::cmp::Reverse;
+
+/// A key pressed in the picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    /// Ctrl-U, clears the query
+    Clear,
+    Up,
+    Down,
+    /// Tab, selects or unselects the co-author under the cursor
+    Toggle,
+    Enter,
+    /// Esc or Ctrl-C
+    Cancel,
+}
+
+/// Parses the keys in the bytes read from a terminal in raw mode
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let input = String::from_utf8_lossy(bytes);
+    let mut chars = input.chars().peekable();
+    let mut keys = vec![];
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' => match chars.peek() {
+                // a lone escape, rather than the start of an escape sequence
+                None => Some(Key::Cancel),
+                Some('[' | 'O') => {
+                    chars.next();
+                    // skip the parameters up to the sequence's final character
+                    let last = chars.by_ref().find(|c| !c.is_ascii_digit() && *c != ';');
+                    match last {
+                        Some('A') => Some(Key::Up),
+                        Some('B') => Some(Key::Down),
+                        _ => None,
+                    }
+                }
+                Some(_) => None,
+            },
+            '\r' | '\n' => Some(Key::Enter),
+            '\t' => Some(Key::Toggle),
+            '\x7f' | '\x08' => Some(Key::Backspace),
+            '\x03' | '\x07' => Some(Key::Cancel),
+            '\x10' => Some(Key::Up),
+            '\x0e' => Some(Key::Down),
+            '\x15' => Some(Key::Clear),
+            c if c.is_control() => None,
+            c => Some(Key::Char(c)),
+        };
+        keys.extend(key);
+    }
+    keys
+}
+
+/// Returns how well `term` matches `text` as a case-insensitive subsequence, if it does
+///
+/// Consecutive characters and characters at the start of a word score higher.
+pub fn fuzzy_score(term: &str, text: &str) -> Option<usize> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut start = 0;
+    let mut last = None;
+    for c in term.to_lowercase().chars() {
+        let index = (start..text.len()).find(|&index| text[index] == c)?;
+        score += 1;
+        if index > 0 && last == Some(index - 1) {
+            score += 2;
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        last = Some(index);
+        start = index + 1;
+    }
+    Some(score)
+}
+
+/// Returns how well the query matches a co-author's initials, name or email, if every word of
+/// it matches one of them
+fn match_score(query: &str, initials: &str, author: &Author) -> Option<usize> {
+    query.split_whitespace().try_fold(0, |score, term| {
+        let initials_score = fuzzy_score(term, initials).map(|score| {
+            // typing someone's initials should find them first
+            if initials.starts_with(term) {
+                score + 10
+            } else {
+                score
+            }
+        });
+        let best = [
+            initials_score,
+            fuzzy_score(term, &author.name),
+            fuzzy_score(term, &author.email),
+        ]
+        .iter()
+        .flatten()
+        .max()
+        .copied()?;
+        Some(score + best)
+    })
+}
+
+/// Orders the co-authors by who authored or co-authored the latest of `commits`, which are
+/// newest first, keeping the file's order for everyone else
+pub fn rank_by_recency(
+    coauthors: LinkedHashMap<String, Author>,
+    commits: &[CommitInfo],
+) -> Vec<(String, Author)> {
+    let mut coauthors: Vec<(Option<usize>, String, Author)> = coauthors
+        .into_iter()
+        .map(|(initials, author)| {
+            let email = author.email.to_lowercase();
+            let last_commit = commits.iter().position(|commit| {
+                commit.author.email.to_lowercase() == email
+                    || commit
+                        .coauthors()
+                        .iter()
+                        .any(|coauthor| coauthor.email.to_lowercase() == email)
+            });
+            (last_commit, initials, author)
+        })
+        .collect();
+
+    // a stable sort keeps the file's order among ties
+    coauthors.sort_by_key(|(last_commit, _, _)| last_commit.unwrap_or(usize::MAX));
+    coauthors
+        .into_iter()
+        .map(|(_, initials, author)| (initials, author))
+        .collect()
+}
+
+/// What the picker ended with
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The initials that were chosen, in the order they were selected
+    Confirm(Vec<String>),
+    Cancel,
+}
+
+/// A fuzzy finder over the co-authors, selecting several of them
+pub struct Picker {
+    coauthors: Vec<(String, Author)>,
+    selected: Vec<String>,
+    query: String,
+    /// The indexes of the co-authors matching the query, best match first
+    matches: Vec<usize>,
+    cursor: usize,
+}
+
+impl Picker {
+    /// Creates a picker over the co-authors, in the order they're listed without a query, with
+    /// the `selected` initials that are among them selected
+    pub fn new(coauthors: Vec<(String, Author)>, selected: &[String]) -> Self {
+        let selected = selected
+            .iter()
+            .filter(|initials| coauthors.iter().any(|(other, _)| other == *initials))
+            .cloned()
+            .collect();
+        let mut picker = Picker {
+            coauthors,
+            selected,
+            query: String::new(),
+            matches: vec![],
+            cursor: 0,
+        };
+        picker.update_matches();
+        picker
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    fn update_matches(&mut self) {
+        let mut matches: Vec<(usize, usize)> = self
+            .coauthors
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (initials, author))| {
+                Some((index, match_score(&self.query, initials, author)?))
+            })
+            .collect();
+        matches.sort_by_key(|(_, score)| Reverse(*score));
+        self.matches = matches.into_iter().map(|(index, _)| index).collect();
+        self.cursor = 0;
+    }
+
+    /// Handles a key, returning the outcome once the picker is done
+    pub fn handle(&mut self, key: Key) -> Option<Outcome> {
+        match key {
+            Key::Char(c) => {
+                self.query.push(c);
+                self.update_matches();
+            }
+            Key::Backspace => {
+                self.query.pop();
+                self.update_matches();
+            }
+            Key::Clear => {
+                self.query.clear();
+                self.update_matches();
+            }
+            Key::Up => self.cursor = self.cursor.saturating_sub(1),
+            Key::Down => {
+                self.cursor = (self.cursor + 1).min(self.matches.len().saturating_sub(1));
+            }
+            Key::Toggle => {
+                if let Some(&index) = self.matches.get(self.cursor) {
+                    let initials = &self.coauthors[index].0;
+                    match self.selected.iter().position(|other| other == initials) {
+                        Some(position) => {
+                            self.selected.remove(position);
+                        }
+                        None => self.selected.push(initials.clone()),
+                    }
+                    self.cursor = (self.cursor + 1).min(self.matches.len() - 1);
+                }
+            }
+            // confirming without a selection goes solo
+            Key::Enter => return Some(Outcome::Confirm(self.selected.clone())),
+            Key::Cancel => return Some(Outcome::Cancel),
+        }
+        None
+    }
+
+    /// Returns the lines to show in a terminal of `height` lines and `width` columns: the
+    /// query, a status line, then as many matching co-authors as fit around the cursor
+    pub fn render(&self, height: usize, width: usize) -> Vec<String> {
+        let selected = if self.selected.is_empty() {
+            String::from("solo")
+        } else {
+            self.selected.join(", ")
+        };
+        let mut lines = vec![
+            format!("> {}", self.query),
+            format!(
+                "  {}/{} | {selected} | tab: select, enter: confirm, esc: cancel",
+                self.matches.len(),
+                self.coauthors.len()
+            ),
+        ];
+
+        let list_height = height.saturating_sub(lines.len()).max(1);
+        let offset = (self.cursor + 1).saturating_sub(list_height);
+        for (position, &index) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(list_height)
+        {
+            let (initials, author) = &self.coauthors[index];
+            let cursor = if position == self.cursor { ">" } else { " " };
+            let checkbox = if self.selected.contains(initials) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            lines.push(format!(
+                "{cursor} {checkbox} {initials}  {} <{}>",
+                author.name, author.email
+            ));
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns a picker over every co-author, with the active mob selected and whoever
+    /// committed most recently first
+    pub fn get_mob_picker(&self) -> Result<Picker> {
+        let coauthors = self.get_all_coauthors()?;
+        let commits = self.get_latest_commits(SUGGEST_DEPTH)?;
+        Ok(Picker::new(
+            rank_by_recency(coauthors, &commits),
+            &self.get_active_initials()?,
+        ))
+    }
+
+    /// Lets the user choose the mob in the terminal, returning the chosen initials, or `None`
+    /// if they cancelled
+    pub fn pick_mob(&self) -> Result<Option<Vec<String>>> {
+        let mut picker = self.get_mob_picker()?;
+        let outcome = terminal::run(&mut picker).map_err(|error| {
+            GitMobError::Io(format!("Unable to open the interactive picker: {error}"))
+        })?;
+        match outcome {
+            Outcome::Confirm(initials) => Ok(Some(initials)),
+            Outcome::Cancel => Ok(None),
+        }
+    }
+}
+
+#[cfg(unix)]
+mod terminal {
+    use super::{parse_keys, Outcome, Picker};
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+    use std::mem::MaybeUninit;
+    use std::os::unix::io::{AsRawFd, RawFd};
+
+    /// Puts the terminal in raw mode, so keys are read as they're pressed, until dropped
+    struct RawMode {
+        fd: RawFd,
+        original: libc::termios,
+    }
+
+    impl RawMode {
+        fn enable(fd: RawFd) -> io::Result<Self> {
+            let mut termios = MaybeUninit::uninit();
+            // SAFETY: tcgetattr initializes the termios when it succeeds
+            let original = unsafe {
+                if libc::tcgetattr(fd, termios.as_mut_ptr()) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                termios.assume_init()
+            };
+
+            let mut raw = original;
+            // SAFETY: raw is a valid termios
+            unsafe {
+                libc::cfmakeraw(&mut raw);
+                if libc::tcsetattr(fd, libc::TCSANOW, &raw) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(RawMode { fd, original })
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            // SAFETY: original is the termios read from the same fd
+            unsafe {
+                libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+            }
+        }
+    }
+
+    /// Returns the terminal's height and width
+    fn size(fd: RawFd) -> (usize, usize) {
+        let mut size = MaybeUninit::<libc::winsize>::zeroed();
+        // SAFETY: TIOCGWINSZ fills in a winsize
+        let size = unsafe {
+            if libc::ioctl(fd, libc::TIOCGWINSZ, size.as_mut_ptr()) != 0 {
+                return (24, 80);
+            }
+            size.assume_init()
+        };
+        match (size.ws_row, size.ws_col) {
+            (0, _) | (_, 0) => (24, 80),
+            (rows, cols) => (usize::from(rows), usize::from(cols)),
+        }
+    }
+
+    fn draw(tty: &mut File, picker: &Picker, height: usize, width: usize) -> io::Result<()> {
+        let mut screen = String::from("\x1b[H\x1b[2J");
+        for (row, line) in picker.render(height, width).iter().enumerate() {
+            screen.push_str(&format!("\x1b[{};1H{line}", row + 1));
+        }
+        // leave the cursor after the query
+        screen.push_str(&format!(
+            "\x1b[1;{}H",
+            (picker.query().chars().count() + 3).min(width)
+        ));
+        tty.write_all(screen.as_bytes())?;
+        tty.flush()
+    }
+
+    /// Runs the picker in the terminal, on its own screen so the scrollback is left alone
+    pub fn run(picker: &mut Picker) -> io::Result<Outcome> {
+        // the terminal rather than stdin and stdout, which may be redirected
+        let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        let fd = tty.as_raw_fd();
+        let raw_mode = RawMode::enable(fd)?;
+        tty.write_all(b"\x1b[?1049h")?;
+
+        let outcome = (|| loop {
+            let (height, width) = size(fd);
+            draw(&mut tty, picker, height, width)?;
+
+            let mut buffer = [0; 64];
+            let read = tty.read(&mut buffer)?;
+            if read == 0 {
+                return Ok(Outcome::Cancel);
+            }
+            for key in parse_keys(&buffer[..read]) {
+                if let Some(outcome) = picker.handle(key) {
+                    return Ok(outcome);
+                }
+            }
+        })();
+
+        tty.write_all(b"\x1b[?1049l")?;
+        tty.flush()?;
+        drop(raw_mode);
+        outcome
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    use super::{Outcome, Picker};
+    use std::io;
+
+    pub fn run(_picker: &mut Picker) -> io::Result<Outcome> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "it's only supported in Unix terminals for now",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit};
+
+    fn get_picker() -> Picker {
+        Picker::new(
+            vec![
+                (
+                    String::from("ab"),
+                    author("Alice Brown", "alice@example.com"),
+                ),
+                (
+                    String::from("cd"),
+                    author("Carol Davis", "carol@example.com"),
+                ),
+                (String::from("ef"), author("Bob Evans", "bob@example.org")),
+            ],
+            &[String::from("cd"), String::from("zz")],
+        )
+    }
+
+    fn type_keys(picker: &mut Picker, keys: &str) -> Option<Outcome> {
+        parse_keys(keys.as_bytes())
+            .into_iter()
+            .find_map(|key| picker.handle(key))
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            vec![
+                Key::Char('a'),
+                Key::Up,
+                Key::Down,
+                Key::Toggle,
+                Key::Backspace,
+                Key::Enter
+            ],
+            parse_keys(b"a\x1b[A\x1bOB\t\x7f\r")
+        );
+        // other escape sequences, such as the right arrow, are ignored
+        assert_eq!(vec![Key::Char('é')], parse_keys("\x1b[1;5Cé".as_bytes()));
+        assert_eq!(vec![Key::Cancel], parse_keys(b"\x1b"));
+        assert_eq!(vec![Key::Cancel], parse_keys(b"\x03"));
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(None, fuzzy_score("xyz", "Alice Brown"));
+        assert!(fuzzy_score("ab", "Alice Brown").is_some());
+        // consecutive characters score higher
+        assert!(fuzzy_score("bro", "Alice Brown") > fuzzy_score("bwn", "Alice Brown"));
+        // as do the starts of words
+        assert!(fuzzy_score("b", "Alice Brown") > fuzzy_score("c", "Alice Brown"));
+    }
+
+    #[test]
+    fn test_picker_filters() {
+        let mut picker = get_picker();
+        assert_eq!(vec![0, 1, 2], picker.matches);
+
+        // matches initials, names and emails, initials first
+        type_keys(&mut picker, "ef");
+        assert_eq!(vec![2], picker.matches);
+        type_keys(&mut picker, "\x15bob");
+        assert_eq!(vec![2], picker.matches);
+        type_keys(&mut picker, "\x15.org");
+        assert_eq!(vec![2], picker.matches);
+        type_keys(&mut picker, "\x15car dav");
+        assert_eq!(vec![1], picker.matches);
+        type_keys(&mut picker, "\x15zzz");
+        assert_eq!(Vec::<usize>::new(), picker.matches);
+    }
+
+    #[test]
+    fn test_picker_selects() {
+        // the selected initials that aren't co-authors are dropped
+        let mut picker = get_picker();
+        assert_eq!(vec![String::from("cd")], picker.selected);
+
+        assert_eq!(
+            Some(Outcome::Confirm(vec![
+                String::from("cd"),
+                String::from("ef")
+            ])),
+            type_keys(&mut picker, "bob\t\r")
+        );
+
+        let mut picker = get_picker();
+        assert_eq!(
+            Some(Outcome::Confirm(vec![String::from("ab")])),
+            type_keys(&mut picker, "\t\t\r")
+        );
+
+        // unselecting everyone goes solo, whoever is under the cursor
+        let mut picker = get_picker();
+        assert_eq!(
+            Some(Outcome::Confirm(vec![])),
+            type_keys(&mut picker, "\x1b[B\t\x1b[B\r")
+        );
+        assert_eq!("  3/3 | solo ", picker.render(3, 13)[1]);
+
+        let mut picker = get_picker();
+        assert_eq!(Some(Outcome::Cancel), type_keys(&mut picker, "ab\x03"));
+    }
+
+    #[test]
+    fn test_picker_render() {
+        let mut picker = get_picker();
+        type_keys(&mut picker, "\x1b[B");
+
+        assert_eq!(
+            vec![
+                "> ",
+                "  3/3 | cd | tab: select, enter: confirm, esc: cancel",
+                "  [ ] ab  Alice Brown <alice@example.com>",
+                "> [x] cd  Carol Davis <carol@example.com>",
+            ],
+            picker.render(4, 80)
+        );
+        assert_eq!(
+            vec!["> ", "  3/3 | cd ", "> [x] cd  C"],
+            picker.render(3, 11)
+        );
+    }
+
+    #[test]
+    fn test_rank_by_recency() {
+        let mut coauthors = LinkedHashMap::new();
+        coauthors.insert(String::from("ab"), author("A B", "ab@example.com"));
+        coauthors.insert(String::from("cd"), author("C D", "cd@example.com"));
+        coauthors.insert(String::from("ef"), author("E F", "ef@example.com"));
+        let commits = [
+            commit(
+                "",
+                author("X", "x@example.com"),
+                "Fix\n\nCo-authored-by: E F <EF@example.com>",
+            ),
+            commit("", author("C D", "cd@example.com"), "Add"),
+            commit("", author("E F", "ef@example.com"), "Init"),
+        ];
+
+        let initials: Vec<String> = rank_by_recency(coauthors, &commits)
+            .into_iter()
+            .map(|(initials, _)| initials)
+            .collect();
+        assert_eq!(vec!["ef", "cd", "ab"], initials);
+    }
+}
@@ -0,0 +1,399 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::message::CommitMessage;
+use crate::GitMob;
+use gix::refs::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix::refs::Target;
+use gix::revision::plumbing::Spec;
+use gix::{ObjectId, Repository};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+
+/// A commit that was given the mob's trailers
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct RewrittenCommit {
+    /// The id before it was rewritten
+    pub old: String,
+    /// The id after, the same when nothing had to change
+    pub new: String,
+    pub subject: String,
+    /// The trailers added to the message
+    pub added: Vec<String>,
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Adds the mob's trailers to the HEAD commit, like "git commit --amend" would
+    pub fn amend_head(&self) -> Result<Vec<RewrittenCommit>> {
+        self.amend_head_in(&self.get_repo()?)
+    }
+
+    fn amend_head_in(&self, repo: &Repository) -> Result<Vec<RewrittenCommit>> {
+        let head = head_id(repo)?;
+        self.rewrite(repo, vec![head], head)
+    }
+
+    /// Adds the mob's trailers to each commit in a range ending at HEAD, such as "@{u}..HEAD"
+    /// or "HEAD~3..", recreating the commits after them and moving the branch
+    ///
+    /// Commits that are already on the upstream are never rewritten.
+    pub fn rewrite_range(&self, range: &str) -> Result<Vec<RewrittenCommit>> {
+        self.rewrite_range_in(&self.get_repo()?, range)
+    }
+
+    fn rewrite_range_in(&self, repo: &Repository, range: &str) -> Result<Vec<RewrittenCommit>> {
+        let bad_revision = |error: &dyn std::fmt::Display| {
+            GitMobError::BadRevision(format!("\"{range}\": {error}"))
+        };
+
+        let head = head_id(repo)?;
+        let spec = repo
+            .rev_parse(range)
+            .map_err(|error| bad_revision(&error))?
+            .detach();
+        let Spec::Range { from, to } = spec else {
+            return Err(bad_revision(
+                &"only a range like \"@{u}..HEAD\" can be rewritten",
+            ));
+        };
+        if to != head {
+            return Err(bad_revision(&"the range has to end at HEAD"));
+        }
+
+        let hidden = ancestors(repo, from).map_err(|error| bad_revision(&error))?;
+        let ids = repo
+            .rev_walk([to])
+            .selected(move |id| !hidden.contains(id))
+            .map_err(|error| bad_revision(&error))?
+            .map(|info| info.map(|info| info.id))
+            .collect::<std::result::Result<Vec<ObjectId>, _>>()
+            .map_err(|error| bad_revision(&error))?;
+        self.rewrite(repo, ids, head)
+    }
+
+    /// Recreates the commits `ids` with the mob's trailers, then points HEAD at the new `head`
+    fn rewrite(
+        &self,
+        repo: &Repository,
+        ids: Vec<ObjectId>,
+        head: ObjectId,
+    ) -> Result<Vec<RewrittenCommit>> {
+        let git_error = |error: &dyn std::fmt::Display| GitMobError::Io(error.to_string());
+
+        let required = self.get_mob_trailers()?;
+        if required.is_empty() {
+            return Err(GitMobError::Io(String::from(
+                "There is no mob to add, run \"git mob <initials>\" first",
+            )));
+        }
+        self.check_upstream(repo, &ids)?;
+
+        let committer = self.get_committer(repo);
+
+        let mut rewritten: HashMap<ObjectId, ObjectId> = HashMap::new();
+        let mut commits = vec![];
+        // parents first, so their children can point at the new ids
+        let mut pending = ids.clone();
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<ObjectId>, Vec<ObjectId>) =
+                pending.into_iter().partition(|id| {
+                    repo.find_commit(*id).is_ok_and(|commit| {
+                        commit.parent_ids().all(|parent| {
+                            !ids.contains(&parent.detach())
+                                || rewritten.contains_key(&parent.detach())
+                        })
+                    })
+                });
+            if ready.is_empty() {
+                return Err(git_error(&"Unable to order the commits to rewrite"));
+            }
+            pending = waiting;
+
+            for id in ready {
+                let commit = repo.find_commit(id).map_err(|error| git_error(&error))?;
+                let mut new: gix::objs::Commit =
+                    commit.decode().map_err(|error| git_error(&error))?.into();
+
+                let message = new.message.to_string();
+                let trailers: Vec<String> = CommitMessage::parse(&message, None)
+                    .missing_required(&required, Some(&new.author.email.to_string()))
+                    .into_iter()
+                    .map(|required| required.trailer.clone())
+                    .collect();
+                let new_message = CommitMessage::parse(&message, None).merge(&trailers);
+                let parents: Vec<ObjectId> = new
+                    .parents
+                    .iter()
+                    .map(|parent| *rewritten.get(parent).unwrap_or(parent))
+                    .collect();
+
+                let changed = new_message != message;
+                let new_id = if !changed && parents == new.parents.to_vec() {
+                    id
+                } else {
+                    new.message = new_message.into();
+                    new.parents = parents.into();
+                    new.committer = committer.clone().unwrap_or_else(|| new.committer.clone());
+                    new.committer.time = to_git_time(self.clock.now());
+                    // a signature wouldn't match the new commit
+                    new.extra_headers
+                        .retain(|(key, _)| key != "gpgsig" && key != "gpgsig-sha256");
+                    repo.write_object(&new)
+                        .map_err(|error| git_error(&error))?
+                        .detach()
+                };
+
+                rewritten.insert(id, new_id);
+                commits.push(RewrittenCommit {
+                    old: id.to_string(),
+                    new: new_id.to_string(),
+                    subject: message.lines().next().unwrap_or_default().to_string(),
+                    added: if changed { trailers } else { vec![] },
+                });
+            }
+        }
+
+        let new_head = rewritten[&head];
+        if new_head != head {
+            repo.edit_reference(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "git mob: add co-authors".into(),
+                    },
+                    expected: PreviousValue::MustExistAndMatch(Target::Object(head)),
+                    new: Target::Object(new_head),
+                },
+                name: gix::refs::FullName::try_from("HEAD").map_err(|error| git_error(&error))?,
+                // moves the branch HEAD is on, or HEAD itself when detached
+                deref: true,
+            })
+            .map_err(|error| git_error(&error))?;
+        }
+
+        Ok(commits)
+    }
+
+    /// Returns an error if any of the commits is already on the upstream of the current branch
+    fn check_upstream(&self, repo: &Repository, ids: &[ObjectId]) -> Result<()> {
+        let Some(head) = repo.head_ref().ok().flatten() else {
+            return Ok(());
+        };
+        let Some(Ok(name)) = head.remote_tracking_ref_name(gix::remote::Direction::Fetch) else {
+            return Ok(());
+        };
+        // an upstream that was never fetched has nothing on it yet
+        let Some(upstream_id) = repo
+            .find_reference(name.as_ref())
+            .ok()
+            .and_then(|mut upstream| upstream.peel_to_id_in_place().ok())
+            .map(|id| id.detach())
+        else {
+            return Ok(());
+        };
+        let name = name.shorten().to_string();
+
+        let pushed = ancestors(repo, upstream_id).map_err(|error| {
+            GitMobError::Io(format!("Unable to read the upstream {name}: {error}"))
+        })?;
+        match ids.iter().find(|id| pushed.contains(*id)) {
+            Some(id) => Err(GitMobError::BadRevision(format!(
+                "{} is already on the upstream {name}, rewriting it would need a force push",
+                id.to_hex_with_len(7)
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns who commits in the repository, the time aside
+    fn get_committer(&self, repo: &Repository) -> Option<gix::actor::Signature> {
+        repo.committer()
+            .and_then(|committer| committer.ok())
+            .map(|committer| committer.to_owned())
+    }
+}
+
+/// Returns the commit HEAD points at
+fn head_id(repo: &Repository) -> Result<ObjectId> {
+    repo.head_id()
+        .map(|id| id.detach())
+        .map_err(|_| GitMobError::BadRevision(String::from("there are no commits yet")))
+}
+
+/// Returns `id` and every commit it descends from
+pub(crate) fn ancestors(
+    repo: &Repository,
+    id: ObjectId,
+) -> std::result::Result<HashSet<ObjectId>, Box<dyn std::error::Error + Send + Sync>> {
+    repo.rev_walk([id])
+        .all()?
+        .map(|info| info.map(|info| info.id).map_err(Into::into))
+        .collect()
+}
+
+fn to_git_time(time: chrono::DateTime<chrono::FixedOffset>) -> gix::date::Time {
+    gix::date::Time::new(time.timestamp(), time.offset().local_minus_utc())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{get_git_mob, git};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn mob() -> Vec<String> {
+        vec![String::from("ab"), String::from("cd")]
+    }
+
+    /// Creates a repository with a commit for each message, as "A B", returning their ids
+    fn init_repo(dir: &Path, messages: &[&str]) -> Vec<String> {
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.name", "E F"]);
+        git(dir, &["config", "user.email", "ef@example.com"]);
+        messages
+            .iter()
+            .map(|message| {
+                git(dir, &["commit", "-q", "--allow-empty", "-m", message]);
+                git(dir, &["rev-parse", "HEAD"])
+            })
+            .collect()
+    }
+
+    fn message(dir: &Path, revision: &str) -> String {
+        git(dir, &["log", "-1", "--format=%B", revision])
+    }
+
+    #[test]
+    fn test_amend_head() {
+        let dir = tempdir().unwrap();
+        let ids = init_repo(dir.path(), &["Start", "Add feature"]);
+        let gm = get_git_mob();
+        gm.write_gitmessage(&mob()).unwrap();
+
+        // the author doesn't credit themselves
+        let commits = gm.amend_head_in(&gix::open(dir.path()).unwrap()).unwrap();
+        let head = git(dir.path(), &["rev-parse", "HEAD"]);
+        assert_eq!(
+            vec![RewrittenCommit {
+                old: ids[1].clone(),
+                new: head.clone(),
+                subject: String::from("Add feature"),
+                added: vec![String::from("Co-authored-by: C D <cd@example.com>")],
+            }],
+            commits
+        );
+        assert_eq!(
+            "Add feature\n\nCo-authored-by: C D <cd@example.com>",
+            message(dir.path(), "HEAD")
+        );
+        // the branch moves, keeping the parent and the author, with the repository's committer
+        assert_eq!(head, git(dir.path(), &["rev-parse", "main"]));
+        assert_eq!(ids[0], git(dir.path(), &["rev-parse", "HEAD~"]));
+        assert_eq!(
+            "A B <ab@example.com> E F <ef@example.com>",
+            git(dir.path(), &["log", "-1", "--format=%an <%ae> %cn <%ce>"])
+        );
+
+        // crediting the mob again changes nothing
+        let commits = gm.amend_head_in(&gix::open(dir.path()).unwrap()).unwrap();
+        assert_eq!(head, commits[0].new);
+        assert!(commits[0].added.is_empty());
+        assert_eq!(head, git(dir.path(), &["rev-parse", "HEAD"]));
+    }
+
+    #[test]
+    fn test_rewrite_range() {
+        let dir = tempdir().unwrap();
+        let ids = init_repo(
+            dir.path(),
+            &[
+                "Start",
+                "Add feature\n\nCo-authored-by: Cee Dee <CD@example.com>",
+                "Fix feature",
+            ],
+        );
+        let gm = get_git_mob();
+        gm.write_gitmessage(&mob()).unwrap();
+        let repo = gix::open(dir.path()).unwrap();
+
+        for range in ["HEAD", "HEAD~2..HEAD~"] {
+            assert!(matches!(
+                gm.rewrite_range_in(&repo, range),
+                Err(GitMobError::BadRevision(_))
+            ));
+        }
+
+        // the commit that already credits the mob is kept, and the one after it points at it
+        let commits = gm.rewrite_range_in(&repo, "HEAD~2..").unwrap();
+        assert_eq!(2, commits.len());
+        assert_eq!(
+            (ids[1].as_str(), ids[1].as_str()),
+            (commits[0].old.as_str(), commits[0].new.as_str())
+        );
+        assert_eq!(ids[2], commits[1].old);
+        assert_ne!(ids[2], commits[1].new);
+        assert_eq!(commits[1].new, git(dir.path(), &["rev-parse", "main"]));
+        assert_eq!(ids[1], git(dir.path(), &["rev-parse", "HEAD~"]));
+        assert_eq!(
+            "Fix feature\n\nCo-authored-by: C D <cd@example.com>",
+            message(dir.path(), "HEAD")
+        );
+
+        // rewriting the commits after a rewritten one remaps their parents
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "Tidy"]);
+        git(dir.path(), &["reset", "-q", "--soft", "HEAD~2"]);
+        git(
+            dir.path(),
+            &["commit", "-q", "--allow-empty", "-m", "Fix again"],
+        );
+        git(dir.path(), &["commit", "-q", "--allow-empty", "-m", "Tidy"]);
+        let repo = gix::open(dir.path()).unwrap();
+        let commits = gm.rewrite_range_in(&repo, "HEAD~2..").unwrap();
+        assert_eq!(
+            vec!["Fix again", "Tidy"],
+            commits
+                .iter()
+                .map(|commit| commit.subject.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(commits[0].new, git(dir.path(), &["rev-parse", "HEAD~"]));
+        assert_eq!(commits[1].new, git(dir.path(), &["rev-parse", "HEAD"]));
+        assert_eq!(ids[1], git(dir.path(), &["rev-parse", "HEAD~2"]));
+    }
+
+    #[test]
+    fn test_rewrite_refuses_upstream_commits() {
+        let dir = tempdir().unwrap();
+        let ids = init_repo(dir.path(), &["Start", "Add feature", "Fix feature"]);
+        git(
+            dir.path(),
+            &["remote", "add", "origin", "https://example.com/repo.git"],
+        );
+        git(
+            dir.path(),
+            &["update-ref", "refs/remotes/origin/main", &ids[1]],
+        );
+        git(
+            dir.path(),
+            &["branch", "-q", "--set-upstream-to", "origin/main"],
+        );
+        let gm = get_git_mob();
+        gm.write_gitmessage(&mob()).unwrap();
+        let repo = gix::open(dir.path()).unwrap();
+
+        let error = gm.rewrite_range_in(&repo, "HEAD~2..").unwrap_err();
+        assert!(matches!(error, GitMobError::BadRevision(_)));
+        assert!(error
+            .to_string()
+            .contains("already on the upstream origin/main"));
+        assert_eq!(ids[2], git(dir.path(), &["rev-parse", "HEAD"]));
+
+        // what isn't pushed yet can still be rewritten
+        let commits = gm.rewrite_range_in(&repo, "@{u}..").unwrap();
+        assert_eq!(1, commits.len());
+        assert_ne!(ids[2], git(dir.path(), &["rev-parse", "HEAD"]));
+    }
+}
@@ -0,0 +1,751 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::{find_roster, git_config, Author, Coauthors, GitMob};
+use gix_config::Source;
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml_edit::DocumentMut;
+
+/// The roster a repository can commit to its root, shared by everyone who clones it
+///
+/// Like the user's coauthors file it can also be TOML or YAML, such as ".git-coauthors.toml".
+pub const REPO_COAUTHORS: &str = ".git-coauthors";
+
+/// Where a co-author comes from
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RosterSource {
+    /// The user's own coauthors file, see `get_coauthors_path`
+    User,
+    /// The repository's `.git-coauthors` file
+    Repo,
+}
+
+impl fmt::Display for RosterSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RosterSource::User => write!(f, "user"),
+            RosterSource::Repo => write!(f, "repo"),
+        }
+    }
+}
+
+/// The format of a roster file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl FromStr for RosterFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(RosterFormat::Json),
+            "toml" => Ok(RosterFormat::Toml),
+            "yaml" | "yml" => Ok(RosterFormat::Yaml),
+            _ => Err(format!("\"{s}\" should be \"json\", \"toml\" or \"yaml\"")),
+        }
+    }
+}
+
+impl fmt::Display for RosterFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RosterFormat::Json => write!(f, "json"),
+            RosterFormat::Toml => write!(f, "toml"),
+            RosterFormat::Yaml => write!(f, "yaml"),
+        }
+    }
+}
+
+impl RosterFormat {
+    /// The extension added to a roster's file name, JSON rosters keep the name git-mob uses
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RosterFormat::Json => "",
+            RosterFormat::Toml => ".toml",
+            RosterFormat::Yaml => ".yaml",
+        }
+    }
+
+    fn from_extension(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+
+    /// Detects the format from the file's extension, or its content if it has none
+    pub fn detect(path: &Path, content: &str) -> Self {
+        if let Some(format) = Self::from_extension(path) {
+            return format;
+        }
+
+        let content = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'));
+        match content {
+            Some(line) if line.starts_with('[') || line.contains('=') => RosterFormat::Toml,
+            Some(line) if !line.starts_with('{') => RosterFormat::Yaml,
+            _ => RosterFormat::Json,
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> std::result::Result<Coauthors, ParseError> {
+        match self {
+            RosterFormat::Json => serde_json::from_str(content).map_err(|error| {
+                ParseError::new(error.to_string(), Some((error.line(), error.column())))
+            }),
+            RosterFormat::Toml => toml::from_str(content).map_err(|error: toml::de::Error| {
+                let location = error
+                    .span()
+                    .map(|span| get_line_column(content, span.start));
+                ParseError::new(error.message().to_string(), location)
+            }),
+            RosterFormat::Yaml => serde_yaml_ng::from_str(content).map_err(|error| {
+                let location = error
+                    .location()
+                    .map(|location| (location.line(), location.column()));
+                ParseError::new(error.to_string(), location)
+            }),
+        }
+    }
+
+    /// Serializes the roster, keeping the comments and layout of a TOML roster's `existing`
+    /// content
+    fn serialize(
+        &self,
+        coauthors: &Coauthors,
+        existing: &str,
+    ) -> std::result::Result<String, String> {
+        match self {
+            RosterFormat::Json => {
+                serde_json::to_string_pretty(coauthors).map_err(|error| error.to_string())
+            }
+            RosterFormat::Toml => update_toml(coauthors, existing),
+            RosterFormat::Yaml => {
+                serde_yaml_ng::to_string(coauthors).map_err(|error| error.to_string())
+            }
+        }
+    }
+}
+
+/// A roster that couldn't be parsed, with where the problem is when it's known
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// The line and column, starting from 1
+    pub location: Option<(usize, usize)>,
+}
+
+impl ParseError {
+    fn new(message: String, location: Option<(usize, usize)>) -> Self {
+        // the JSON and YAML messages end with the location already
+        let message = match location {
+            Some((line, column)) => message
+                .strip_suffix(&format!(" at line {line} column {column}"))
+                .map(String::from)
+                .unwrap_or(message),
+            None => message,
+        };
+        ParseError { message, location }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "{} at line {line} column {column}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Returns the line and column, starting from 1, of a byte offset in `content`
+fn get_line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Returns whether YAML content has comments, a "#" at the start of a line or after whitespace
+/// that isn't in a quoted string
+fn has_yaml_comments(content: &str) -> bool {
+    let mut quote = None;
+    let mut previous = '\n';
+    for c in content.chars() {
+        match quote {
+            Some(q) if c == q && previous != '\\' => quote = None,
+            Some(_) => {}
+            // a quote only starts a string at the start of a value, as in "name: O'Brien"
+            None if (c == '"' || c == '\'')
+                && (previous.is_whitespace() || "[{,".contains(previous)) =>
+            {
+                quote = Some(c)
+            }
+            None if c == '#' && previous.is_whitespace() => return true,
+            None => {}
+        }
+        previous = c;
+    }
+    false
+}
+
+/// Updates a TOML roster in place, only touching the entries that changed
+fn update_toml(coauthors: &Coauthors, existing: &str) -> std::result::Result<String, String> {
+    let mut document: DocumentMut = existing
+        .parse()
+        .map_err(|error: toml_edit::TomlError| error.to_string())?;
+    let table = document.entry("coauthors").or_insert(toml_edit::table());
+    if let Some(table) = table.as_table_mut() {
+        // "[coauthors.ab]" rather than an empty "[coauthors]" header
+        table.set_implicit(true);
+    }
+    let table = table
+        .as_table_like_mut()
+        .ok_or("\"coauthors\" should be a table")?;
+
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(initials, _)| initials.to_string())
+        .filter(|initials| !coauthors.coauthors.contains_key(initials))
+        .collect();
+    for initials in removed {
+        table.remove(&initials);
+    }
+
+    for (initials, author) in &coauthors.coauthors {
+        let entry = table
+            .entry(initials)
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or(format!("\"{initials}\" should be a table"))?;
+        for (key, value) in [("name", &author.name), ("email", &author.email)] {
+            // replacing an unchanged value would drop its comment
+            if entry.get(key).and_then(|item| item.as_str()) != Some(value) {
+                entry.insert(key, toml_edit::value(value));
+            }
+        }
+    }
+
+    update_toml_groups(&mut document, &coauthors.groups)?;
+
+    Ok(document.to_string())
+}
+
+/// Updates the groups of a TOML roster in place, only touching the groups that changed
+fn update_toml_groups(
+    document: &mut DocumentMut,
+    groups: &LinkedHashMap<String, Vec<String>>,
+) -> std::result::Result<(), String> {
+    if groups.is_empty() {
+        document.remove("groups");
+        return Ok(());
+    }
+
+    let table = document
+        .entry("groups")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or("\"groups\" should be a table")?;
+
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| !groups.contains_key(name))
+        .collect();
+    for name in removed {
+        table.remove(&name);
+    }
+
+    for (name, members) in groups {
+        let members: Vec<&str> = members.iter().map(String::as_str).collect();
+        let current: Option<Vec<&str>> = table
+            .get(name)
+            .and_then(|item| item.as_array())
+            .map(|array| array.iter().filter_map(|member| member.as_str()).collect());
+        // replacing an unchanged group would drop its comment
+        if current.as_ref() != Some(&members) {
+            let members: toml_edit::Array = members.into_iter().collect();
+            table.insert(name, toml_edit::value(members));
+        }
+    }
+    Ok(())
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Reads the whole roster at `path`, an empty one if it doesn't exist
+    pub(crate) fn read_roster(&self, path: &Path) -> Result<Coauthors> {
+        let coauthors_str = self.file_actions.read(path).unwrap_or_default();
+
+        if coauthors_str.trim().is_empty() {
+            return Ok(Coauthors::default());
+        }
+
+        RosterFormat::detect(path, &coauthors_str)
+            .parse(&coauthors_str)
+            .map_err(|error| GitMobError::BadRoster {
+                path: path.to_path_buf(),
+                message: error.to_string(),
+            })
+    }
+
+    pub(crate) fn read_coauthors(&self, path: &Path) -> Result<LinkedHashMap<String, Author>> {
+        Ok(self.read_roster(path)?.coauthors)
+    }
+
+    /// Writes the whole roster to `path`, in the format the file is already in
+    pub(crate) fn write_roster_to(
+        &self,
+        path: &Path,
+        format: Option<RosterFormat>,
+        roster: &Coauthors,
+    ) -> Result<()> {
+        let existing = self.file_actions.read(path).unwrap_or_default();
+        let format = format.unwrap_or_else(|| RosterFormat::detect(path, &existing));
+
+        // a YAML roster is written from scratch, which would lose its comments
+        if format == RosterFormat::Yaml && has_yaml_comments(&existing) {
+            return Err(GitMobError::Io(format!(
+                "{} has comments that changing it would lose, edit it by hand or convert it to TOML with \"git mob convert-coauthors toml\"",
+                path.display()
+            )));
+        }
+
+        let coauthors_str =
+            format
+                .serialize(roster, &existing)
+                .map_err(|message| GitMobError::BadRoster {
+                    path: path.to_path_buf(),
+                    message,
+                })?;
+
+        self.file_actions
+            .write(path, &coauthors_str)
+            .map_err(GitMobError::Io)
+    }
+
+    /// Writes the co-authors to `path`, in the format the file is already in, keeping the rest
+    /// of the roster such as its groups
+    pub(crate) fn write_coauthors_to(
+        &self,
+        path: &Path,
+        format: Option<RosterFormat>,
+        coauthors: LinkedHashMap<String, Author>,
+    ) -> Result<()> {
+        let existing = self.file_actions.read(path).unwrap_or_default();
+        let format = format.unwrap_or_else(|| RosterFormat::detect(path, &existing));
+        let mut roster = format.parse(&existing).unwrap_or_default();
+        roster.coauthors = coauthors;
+        self.write_roster_to(path, Some(format), &roster)
+    }
+
+    /// Converts the user's coauthors file to another format, returning its new path
+    ///
+    /// The file is renamed to match the format, for example "git-coauthors.toml".
+    pub fn convert_coauthors(&self, format: RosterFormat) -> Result<PathBuf> {
+        let path = self.get_coauthors_path()?;
+        let roster = self.read_roster(&path)?;
+
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let stem = match RosterFormat::from_extension(&path) {
+            Some(_) => file_name
+                .rsplit_once('.')
+                .map_or(file_name.as_str(), |(stem, _)| stem),
+            None => &file_name,
+        };
+        let new_path = path.with_file_name(format!("{stem}{}", format.extension()));
+
+        if new_path != path {
+            // start from scratch rather than updating whatever might be there
+            self.file_actions
+                .remove(&new_path)
+                .map_err(GitMobError::Io)?;
+        }
+        self.write_roster_to(&new_path, Some(format), &roster)?;
+        if new_path != path {
+            self.file_actions.remove(&path).map_err(GitMobError::Io)?;
+        }
+
+        Ok(new_path)
+    }
+
+    /// Returns the co-authors in the user's own coauthors file, which is the one that gets edited
+    pub fn get_user_coauthors(&self) -> Result<LinkedHashMap<String, Author>> {
+        self.read_coauthors(&self.get_coauthors_path()?)
+    }
+
+    /// Returns the path of the repository's `.git-coauthors`, when in a repository with a
+    /// worktree
+    pub fn get_repo_coauthors_path(&self) -> Result<Option<PathBuf>> {
+        match self.get_repo() {
+            Ok(repo) => Ok(repo.work_dir().map(|work_dir| {
+                let path = find_roster(work_dir, REPO_COAUTHORS)
+                    .unwrap_or_else(|| work_dir.join(REPO_COAUTHORS));
+                std::path::absolute(&path).unwrap_or(path)
+            })),
+            Err(GitMobError::NotInRepo) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns the repository's `.git-coauthors` path and content, if it has one
+    fn read_repo_roster(&self) -> Result<Option<(PathBuf, String)>> {
+        let Some(path) = self.get_repo_coauthors_path()? else {
+            return Ok(None);
+        };
+        Ok(self
+            .file_actions
+            .read(&path)
+            .ok()
+            .map(|content| (path, content)))
+    }
+
+    /// Returns whether `content` is what was last trusted with `trust_repo_coauthors`
+    fn is_trusted_roster(&self, content: &str) -> Result<bool> {
+        let trusted = self.get_git_config_value("mob.trustedCoauthors")?;
+        Ok(trusted.is_some_and(|trusted| trusted == roster_hash(content)))
+    }
+
+    /// Returns the co-authors in the repository's `.git-coauthors`
+    ///
+    /// A cloned repository could otherwise slip in an identity to credit, so the file is only
+    /// used once it has been trusted, and again after every change to it.
+    pub fn get_repo_coauthors(&self) -> Result<LinkedHashMap<String, Author>> {
+        Ok(self.get_repo_roster()?.coauthors)
+    }
+
+    /// Returns the repository's `.git-coauthors` roster, or an empty one if it isn't trusted
+    pub(crate) fn get_repo_roster(&self) -> Result<Coauthors> {
+        match self.read_repo_roster()? {
+            Some((path, content)) if self.is_trusted_roster(&content)? => self.read_roster(&path),
+            _ => Ok(Coauthors::default()),
+        }
+    }
+
+    /// Returns the path of the repository's `.git-coauthors` if it's being ignored because it
+    /// hasn't been trusted
+    pub fn get_untrusted_repo_coauthors_path(&self) -> Result<Option<PathBuf>> {
+        match self.read_repo_roster()? {
+            Some((path, content)) if !self.is_trusted_roster(&content)? => Ok(Some(path)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Trusts the current content of the repository's `.git-coauthors`, returning its path
+    ///
+    /// The trust is kept in the repository's own git config, which a clone can't set.
+    pub fn trust_repo_coauthors(&self) -> Result<PathBuf> {
+        let repo = self.get_repo()?;
+        let Some((path, content)) = self.read_repo_roster()? else {
+            return Err(GitMobError::Io(format!(
+                "There is no {REPO_COAUTHORS} file in the root of this repository"
+            )));
+        };
+        // make sure it can be used before trusting it
+        self.read_coauthors(&path)?;
+
+        git_config::set_value(
+            &repo.common_dir().join("config"),
+            Source::Local,
+            "mob.trustedCoauthors",
+            &roster_hash(&content),
+        )?;
+        Ok(path)
+    }
+
+    /// Returns every co-author along with the roster they come from
+    pub fn get_all_coauthors_with_source(
+        &self,
+    ) -> Result<LinkedHashMap<String, (Author, RosterSource)>> {
+        Ok(merge_coauthors(
+            self.get_user_coauthors()?,
+            self.get_repo_coauthors()?,
+        ))
+    }
+}
+
+/// Merges the user's co-authors with the repository's, the user's entries taking precedence
+fn merge_coauthors(
+    user: LinkedHashMap<String, Author>,
+    repo: LinkedHashMap<String, Author>,
+) -> LinkedHashMap<String, (Author, RosterSource)> {
+    let mut coauthors: LinkedHashMap<String, (Author, RosterSource)> = user
+        .into_iter()
+        .map(|(initials, author)| (initials, (author, RosterSource::User)))
+        .collect();
+    for (initials, author) in repo {
+        coauthors
+            .entry(initials)
+            .or_insert((author, RosterSource::Repo));
+    }
+    coauthors
+}
+
+/// Returns the id git would give the roster's content, used to recognise a trusted roster
+fn roster_hash(content: &str) -> String {
+    gix::objs::compute_hash(
+        gix::hash::Kind::Sha1,
+        gix::object::Kind::Blob,
+        content.as_bytes(),
+    )
+    .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, get_git_mob};
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_coauthors() {
+        let mut user = LinkedHashMap::new();
+        user.insert(String::from("ab"), author("A B", "ab@example.com"));
+        let mut repo = LinkedHashMap::new();
+        repo.insert(String::from("cd"), author("C D", "cd@example.com"));
+        repo.insert(
+            String::from("ab"),
+            author("Someone Else", "else@example.com"),
+        );
+
+        let mut expected = LinkedHashMap::new();
+        expected.insert(
+            String::from("ab"),
+            (author("A B", "ab@example.com"), RosterSource::User),
+        );
+        expected.insert(
+            String::from("cd"),
+            (author("C D", "cd@example.com"), RosterSource::Repo),
+        );
+        assert_eq!(expected, merge_coauthors(user, repo));
+    }
+
+    #[test]
+    fn test_untrusted_repo_coauthors() {
+        let gm = get_git_mob();
+        let repo_coauthors_path = gm.get_repo_coauthors_path().unwrap().unwrap();
+
+        assert_eq!(None, gm.get_untrusted_repo_coauthors_path().unwrap());
+
+        gm.file_actions
+            .write(
+                &repo_coauthors_path,
+                &json!({
+                    "coauthors": {
+                        "ef": {
+                            "name": "E F",
+                            "email": "ef@example.com"
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Some(repo_coauthors_path),
+            gm.get_untrusted_repo_coauthors_path().unwrap()
+        );
+        assert!(!gm.get_all_coauthors().unwrap().contains_key("ef"));
+    }
+
+    #[test]
+    fn test_detect_roster_format() {
+        let path = Path::new("git-coauthors");
+        assert_eq!(RosterFormat::Json, RosterFormat::detect(path, ""));
+        assert_eq!(
+            RosterFormat::Json,
+            RosterFormat::detect(path, "{\"coauthors\": {}}")
+        );
+        assert_eq!(
+            RosterFormat::Toml,
+            RosterFormat::detect(path, "# the team\n[coauthors.ab]\n")
+        );
+        assert_eq!(
+            RosterFormat::Yaml,
+            RosterFormat::detect(path, "# the team\ncoauthors:\n")
+        );
+        // the extension wins
+        assert_eq!(
+            RosterFormat::Yaml,
+            RosterFormat::detect(Path::new("git-coauthors.yml"), "{}")
+        );
+    }
+
+    #[test]
+    fn test_read_roster_formats() {
+        let gm = get_git_mob();
+        let expected = gm.get_user_coauthors().unwrap();
+
+        let toml = "
+# the team
+[coauthors.ab]
+name = \"A B\"
+email = \"ab@example.com\"
+
+[coauthors.cd]
+name = \"C D\" # on loan
+email = \"cd@example.com\"
+";
+        let yaml = "
+# the team
+coauthors:
+  ab:
+    name: A B
+    email: ab@example.com
+  cd:
+    name: C D # on loan
+    email: cd@example.com
+";
+        for (path, content) in [("/roster.toml", toml), ("/roster.yaml", yaml)] {
+            let path = Path::new(path);
+            gm.file_actions.write(path, content).unwrap();
+            assert_eq!(expected, gm.read_coauthors(path).unwrap());
+        }
+
+        let path = Path::new("/roster.toml");
+        gm.file_actions.write(path, "[coauthors.ab\n").unwrap();
+        assert!(matches!(
+            gm.read_coauthors(path),
+            Err(GitMobError::BadRoster { .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_toml_keeps_comments() {
+        let gm = get_git_mob();
+        let path = Path::new("/roster.toml");
+        gm.file_actions
+            .write(
+                path,
+                "# the team
+[coauthors.ab]
+name = \"A B\" # lead
+email = \"ab@example.com\"
+
+# on loan
+[coauthors.cd]
+name = \"C D\"
+email = \"cd@example.com\"
+",
+            )
+            .unwrap();
+
+        let mut coauthors = gm.read_coauthors(path).unwrap();
+        coauthors.remove("cd");
+        coauthors.get_mut("ab").unwrap().email = String::from("a.b@example.com");
+        coauthors.insert(String::from("ef"), author("E F", "ef@example.com"));
+        gm.write_coauthors_to(path, None, coauthors).unwrap();
+
+        assert_eq!(
+            "# the team
+[coauthors.ab]
+name = \"A B\" # lead
+email = \"a.b@example.com\"
+
+[coauthors.ef]
+name = \"E F\"
+email = \"ef@example.com\"
+",
+            gm.file_actions.read(path).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_yaml_with_comments() {
+        let gm = get_git_mob();
+        let path = Path::new("/roster.yaml");
+        let commented = "# the team
+coauthors:
+  ab:
+    name: A B
+    email: ab@example.com
+";
+        gm.file_actions.write(path, commented).unwrap();
+        assert!(has_yaml_comments("name: O'Brien # lead\n"));
+
+        let mut coauthors = gm.read_coauthors(path).unwrap();
+        coauthors.insert(String::from("cd"), author("C D", "cd@example.com"));
+        assert!(matches!(
+            gm.write_coauthors_to(path, None, coauthors.clone()),
+            Err(GitMobError::Io(_))
+        ));
+        assert_eq!(commented, gm.file_actions.read(path).unwrap());
+
+        // a "#" in a quoted name or an email isn't a comment
+        gm.file_actions
+            .write(
+                path,
+                "coauthors:\n  ab:\n    name: \"A #1\"\n    email: a#b@example.com\n",
+            )
+            .unwrap();
+        gm.write_coauthors_to(path, None, coauthors).unwrap();
+        assert_eq!(
+            vec!["ab", "cd"],
+            gm.read_coauthors(path).unwrap().keys().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_write_toml_groups() {
+        let gm = get_git_mob();
+        let path = Path::new("/roster.toml");
+        gm.file_actions
+            .write(
+                path,
+                "[coauthors.ab]
+name = \"A B\"
+email = \"ab@example.com\"
+
+[groups]
+core = [\"ab\", \"cd\"] # the core team
+old = [\"ab\"]
+",
+            )
+            .unwrap();
+
+        let mut roster = gm.read_roster(path).unwrap();
+        roster.groups.remove("old");
+        roster
+            .groups
+            .insert(String::from("ui"), vec![String::from("ab")]);
+        gm.write_roster_to(path, None, &roster).unwrap();
+
+        assert_eq!(
+            "[coauthors.ab]
+name = \"A B\"
+email = \"ab@example.com\"
+
+[groups]
+core = [\"ab\", \"cd\"] # the core team
+ui = [\"ab\"]
+",
+            gm.file_actions.read(path).unwrap()
+        );
+
+        roster.groups.clear();
+        gm.write_roster_to(path, None, &roster).unwrap();
+        assert!(!gm.file_actions.read(path).unwrap().contains("groups"));
+    }
+
+    #[test]
+    fn test_roster_hash() {
+        // the same id as "git hash-object"
+        assert_eq!(
+            "ce013625030ba8dba906f756967f9e9ca394464a",
+            roster_hash("hello\n")
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::message::CommitMessage;
+use crate::{Author, GitMob, MobScope, GITMESSAGE};
+use clap::ValueEnum;
+use gix::revision::plumbing::Spec;
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Someone who authored or co-authored a squashed commit, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct SquashCoauthor {
+    /// Their initials, if they're in the coauthors file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initials: Option<String>,
+    #[serde(flatten)]
+    pub author: Author,
+}
+
+/// Where to write the trailers of a squash merge
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SquashTarget {
+    /// The message "git merge --squash" prepares, .git/SQUASH_MSG
+    SquashMsg,
+    /// The mob's .gitmessage template, until the mob is set again
+    Gitmessage,
+}
+
+/// Returns everyone who authored or co-authored the commits, oldest first, without `user_email`
+///
+/// People are matched by email, ignoring case, and those in the roster get its name and email.
+pub fn collect_squash_coauthors(
+    commits: &[CommitInfo],
+    roster: &LinkedHashMap<String, Author>,
+    user_email: &str,
+) -> Vec<SquashCoauthor> {
+    let mut seen = HashSet::from([user_email.to_lowercase()]);
+    let mut coauthors = vec![];
+    // the commits are newest first
+    for commit in commits.iter().rev() {
+        let mut people = vec![commit.author.clone()];
+        people.extend(commit.coauthors());
+
+        for person in people {
+            let coauthor = match roster
+                .iter()
+                .find(|(_, author)| author.email.eq_ignore_ascii_case(&person.email))
+            {
+                Some((initials, author)) => SquashCoauthor {
+                    initials: Some(initials.to_string()),
+                    author: author.clone(),
+                },
+                None => SquashCoauthor {
+                    initials: None,
+                    author: person,
+                },
+            };
+            if seen.insert(coauthor.author.email.to_lowercase()) {
+                coauthors.push(coauthor);
+            }
+        }
+    }
+    coauthors
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns everyone who authored or co-authored the commits in a range such as
+    /// "main..feature", except yourself
+    pub fn get_squash_coauthors(&self, range: &str) -> Result<Vec<SquashCoauthor>> {
+        let repo = self.get_repo()?;
+        // a single revision would be its whole history
+        let spec = repo
+            .rev_parse(range)
+            .map_err(|error| GitMobError::BadRevision(format!("\"{range}\": {error}")))?
+            .detach();
+        if !matches!(spec, Spec::Range { .. }) {
+            return Err(GitMobError::BadRevision(format!(
+                "\"{range}\": only a range like \"main..feature\" can be squashed"
+            )));
+        }
+
+        let commits = self.get_commits_in(&repo, range, None, None)?;
+        Ok(collect_squash_coauthors(
+            &commits,
+            &self.get_all_coauthors()?,
+            &self.get_git_author()?.email,
+        ))
+    }
+
+    /// Returns a trailer, in the configured format, for each of the squash's co-authors
+    pub fn get_squash_trailers(&self, coauthors: &[SquashCoauthor]) -> Result<Vec<String>> {
+        let trailer = self.get_trailer_format()?;
+        Ok(coauthors
+            .iter()
+            .map(|coauthor| {
+                trailer.format(
+                    coauthor.initials.as_deref().unwrap_or_default(),
+                    &coauthor.author,
+                )
+            })
+            .collect())
+    }
+
+    /// Adds the trailers to the squash message or the mob's template, returning its path
+    pub fn write_squash_trailers(
+        &self,
+        trailers: &[String],
+        target: SquashTarget,
+    ) -> Result<PathBuf> {
+        let path = match target {
+            SquashTarget::SquashMsg => self.get_repo()?.path().join("SQUASH_MSG"),
+            SquashTarget::Gitmessage => {
+                let scope = self.get_active_scope()?.unwrap_or(MobScope::Local);
+                self.get_scope_path(scope, GITMESSAGE)?
+            }
+        };
+
+        // the log of squashed commits quotes their trailers indented, which git doesn't take
+        // for a trailer block
+        let message = self.file_actions.read(&path).unwrap_or_default();
+        let comment = self.get_comment_prefix()?;
+        let new_message = CommitMessage::parse(&message, Some(&comment)).merge(trailers);
+        self.file_actions
+            .write(&path, &new_message)
+            .map_err(GitMobError::Io)?;
+        Ok(std::path::absolute(&path).unwrap_or(path))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit, get_git_mob};
+
+    #[test]
+    fn test_collect_squash_coauthors() {
+        let mut roster = LinkedHashMap::new();
+        roster.insert(String::from("cd"), author("C D", "cd@example.com"));
+        let commits = [
+            commit("", author("E F", "ef@example.com"),
+                "Fix\n\nCo-authored-by: Cee Dee <CD@example.com>\nCo-authored-by: A B <ab@example.com>\n",
+            ),
+            commit("", author("A B", "AB@example.com"),
+                "Add\n\nCo-authored-by: C D <cd@example.com>\n",
+            ),
+        ];
+
+        assert_eq!(
+            vec![
+                SquashCoauthor {
+                    initials: Some(String::from("cd")),
+                    author: author("C D", "cd@example.com"),
+                },
+                SquashCoauthor {
+                    initials: None,
+                    author: author("E F", "ef@example.com"),
+                },
+            ],
+            collect_squash_coauthors(&commits, &roster, "ab@example.com")
+        );
+    }
+
+    #[test]
+    fn test_get_squash_coauthors_range() {
+        let gm = get_git_mob();
+
+        for revision in ["HEAD", "HEAD~1...HEAD", "does-not-exist..HEAD"] {
+            let error = gm.get_squash_coauthors(revision).unwrap_err();
+            assert!(matches!(error, GitMobError::BadRevision(_)));
+        }
+        gm.get_squash_coauthors("HEAD~1..HEAD").unwrap();
+    }
+
+    #[test]
+    fn test_write_squash_trailers() {
+        let gm = get_git_mob();
+        let path = gm.get_repo().unwrap().path().join("SQUASH_MSG");
+        let message = "Squashed commit of the following:
+
+commit 1234
+Author: E F <ef@example.com>
+
+    Fix
+
+    Co-authored-by: C D <cd@example.com>
+";
+        gm.file_actions.write(&path, message).unwrap();
+        let trailers = [
+            String::from("Co-authored-by: C D <cd@example.com>"),
+            String::from("Co-authored-by: E F <ef@example.com>"),
+        ];
+
+        // the quoted trailers aren't taken for the message's own
+        let written = gm
+            .write_squash_trailers(&trailers, SquashTarget::SquashMsg)
+            .unwrap();
+        assert_eq!(std::path::absolute(&path).unwrap(), written);
+        let squash_message = format!("{message}\n{}\n", trailers.join("\n"));
+        assert_eq!(squash_message, gm.file_actions.read(&path).unwrap());
+
+        // writing them again doesn't repeat them
+        gm.write_squash_trailers(&trailers, SquashTarget::SquashMsg)
+            .unwrap();
+        assert_eq!(squash_message, gm.file_actions.read(&path).unwrap());
+    }
+
+    #[test]
+    fn test_write_squash_trailers_to_gitmessage() {
+        let gm = get_git_mob();
+        gm.write_gitmessage(&[String::from("cd")]).unwrap();
+        let trailers = [
+            String::from("Co-authored-by: C D <cd@example.com>"),
+            String::from("Co-authored-by: E F <ef@example.com>"),
+        ];
+
+        let written = gm
+            .write_squash_trailers(&trailers, SquashTarget::Gitmessage)
+            .unwrap();
+        assert_eq!(
+            std::path::absolute(gm.get_gitmessage_path().unwrap()).unwrap(),
+            written
+        );
+        assert_eq!(
+            "\n\nCo-authored-by: C D <cd@example.com>\nCo-authored-by: E F <ef@example.com>\n",
+            gm.get_gitmessage().unwrap()
+        );
+    }
+}
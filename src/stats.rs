@@ -0,0 +1,265 @@
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::{Author, GitMob};
+use chrono::NaiveDate;
+use gix::Repository;
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How often people committed and paired over a range of history
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct PairingStats {
+    /// How many commits were looked at
+    pub commits: usize,
+    /// Everyone who authored or co-authored a commit, most commits first
+    pub people: Vec<PersonStats>,
+    /// Every pair of people who shared a commit, most commits first
+    pub pairs: Vec<PairStats>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct PersonStats {
+    /// Their initials from the coauthors file, or their email if they aren't in it
+    pub person: String,
+    pub commits: usize,
+    /// How many of their commits were shared with someone else
+    pub paired: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct PairStats {
+    pub pair: [String; 2],
+    pub commits: usize,
+}
+
+/// Counts commits per person and per pair, naming people by their initials where possible
+///
+/// People are matched to the coauthors file by email, ignoring case, so the same person is
+/// counted once whether they authored or co-authored a commit.
+pub fn get_pairing_stats(
+    commits: &[CommitInfo],
+    coauthors: &LinkedHashMap<String, Author>,
+) -> PairingStats {
+    let initials_by_email: HashMap<String, &String> = coauthors
+        .iter()
+        .map(|(initials, author)| (author.email.to_lowercase(), initials))
+        .collect();
+    let person = |author: &Author| match initials_by_email.get(&author.email.to_lowercase()) {
+        Some(initials) => initials.to_string(),
+        None => author.email.to_lowercase(),
+    };
+
+    let mut people: HashMap<String, PersonStats> = HashMap::new();
+    let mut pairs: HashMap<[String; 2], usize> = HashMap::new();
+
+    for commit in commits {
+        let mut mob = vec![person(&commit.author)];
+        for coauthor in commit.coauthors() {
+            let coauthor = person(&coauthor);
+            if !mob.contains(&coauthor) {
+                mob.push(coauthor);
+            }
+        }
+
+        for member in &mob {
+            let stats = people
+                .entry(member.to_string())
+                .or_insert_with(|| PersonStats {
+                    person: member.to_string(),
+                    commits: 0,
+                    paired: 0,
+                });
+            stats.commits += 1;
+            if mob.len() > 1 {
+                stats.paired += 1;
+            }
+        }
+
+        for (i, first) in mob.iter().enumerate() {
+            for second in &mob[i + 1..] {
+                let mut pair = [first.to_string(), second.to_string()];
+                pair.sort();
+                *pairs.entry(pair).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut people: Vec<PersonStats> = people.into_values().collect();
+    people.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.person.cmp(&b.person)));
+
+    let mut pairs: Vec<PairStats> = pairs
+        .into_iter()
+        .map(|(pair, commits)| PairStats { pair, commits })
+        .collect();
+    pairs.sort_by(|a, b| b.commits.cmp(&a.commits).then(a.pair.cmp(&b.pair)));
+
+    PairingStats {
+        commits: commits.len(),
+        people,
+        pairs,
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the pairing stats for a revision or range, see `get_commits`
+    pub fn get_stats(
+        &self,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<PairingStats> {
+        self.get_stats_in(&self.get_repo()?, revision, since, until)
+    }
+
+    fn get_stats_in(
+        &self,
+        repo: &Repository,
+        revision: &str,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<PairingStats> {
+        let commits = self.get_commits_in(repo, revision, since, until)?;
+        Ok(get_pairing_stats(&commits, &self.get_all_coauthors()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit, get_git_mob, git};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_get_pairing_stats() {
+        let gm = get_git_mob();
+        let commits = [
+            commit("", author("Someone", "AB@example.com"),
+                "Pair\n\nCo-authored-by: C D <cd@example.com>\n",
+            ),
+            // co-authoring yourself doesn't make a pair
+            commit("", author("Someone", "cd@example.com"),
+                "Pair again\n\nCo-authored-by: C D <cd@example.com>\nCo-authored-by: A B <ab@example.com>\n",
+            ),
+            commit("", author("Someone", "ab@example.com"),
+                "Mob\n\nCo-authored-by: E F <ef@example.com>\nCo-authored-by: C D <cd@example.com>\n",
+            ),
+            commit("", author("Someone", "ab@example.com"), "Solo\n"),
+        ];
+
+        let pair = |first: &str, second: &str, commits| PairStats {
+            pair: [String::from(first), String::from(second)],
+            commits,
+        };
+        assert_eq!(
+            PairingStats {
+                commits: 4,
+                people: vec![
+                    PersonStats {
+                        person: String::from("ab"),
+                        commits: 4,
+                        paired: 3,
+                    },
+                    PersonStats {
+                        person: String::from("cd"),
+                        commits: 3,
+                        paired: 3,
+                    },
+                    PersonStats {
+                        person: String::from("ef@example.com"),
+                        commits: 1,
+                        paired: 1,
+                    },
+                ],
+                pairs: vec![
+                    pair("ab", "cd", 3),
+                    pair("ab", "ef@example.com", 1),
+                    pair("cd", "ef@example.com", 1),
+                ],
+            },
+            get_pairing_stats(&commits, &gm.get_all_coauthors().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_get_stats_since_until() {
+        let gm = get_git_mob();
+        let dir = tempdir().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        for (date, message) in [
+            ("2023-12-31T23:59:59+00:00", "Before"),
+            ("2024-01-01T00:00:00+00:00", "First day"),
+            (
+                "2024-01-02T12:00:00+00:00",
+                "Pair\n\nCo-authored-by: C D <cd@example.com>",
+            ),
+            ("2024-01-03T23:59:59+00:00", "Last day"),
+            // the days are the clock's, so this is still the 3rd
+            ("2024-01-04T01:00:00+02:00", "Last day elsewhere"),
+            ("2024-01-04T00:00:00+00:00", "After"),
+        ] {
+            git(
+                dir.path(),
+                &[
+                    "commit",
+                    "-q",
+                    "--allow-empty",
+                    "--date",
+                    date,
+                    "-m",
+                    message,
+                ],
+            );
+        }
+        let repo = gix::open(dir.path()).unwrap();
+        let date = |s: &str| Some(NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap());
+        let subjects = |since, until| {
+            gm.get_commits_in(&repo, "HEAD", since, until)
+                .unwrap()
+                .iter()
+                .map(|commit| commit.message.lines().next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // both days are included whole
+        assert_eq!(
+            vec!["Last day elsewhere", "Last day", "Pair", "First day"],
+            subjects(date("2024-01-01"), date("2024-01-03"))
+        );
+        assert_eq!(
+            vec![
+                "After",
+                "Last day elsewhere",
+                "Last day",
+                "Pair",
+                "First day"
+            ],
+            subjects(date("2024-01-01"), None)
+        );
+        assert_eq!(
+            vec![
+                "Last day elsewhere",
+                "Last day",
+                "Pair",
+                "First day",
+                "Before"
+            ],
+            subjects(None, date("2024-01-03"))
+        );
+        assert!(subjects(date("2024-01-05"), None).is_empty());
+
+        let stats = gm
+            .get_stats_in(&repo, "HEAD", date("2024-01-02"), date("2024-01-02"))
+            .unwrap();
+        assert_eq!(1, stats.commits);
+        assert_eq!(
+            vec![PairStats {
+                pair: [String::from("ab"), String::from("cd")],
+                commits: 1,
+            }],
+            stats.pairs
+        );
+    }
+}
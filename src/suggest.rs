@@ -0,0 +1,129 @@
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::file_actions::FileActions;
+use crate::history::CommitInfo;
+use crate::{Author, GitMob};
+use linked_hash_map::LinkedHashMap;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+/// How many of the latest commits are searched for suggestions
+pub const SUGGEST_DEPTH: usize = 1000;
+
+/// A co-author who worked on the files being changed
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub initials: String,
+    #[serde(flatten)]
+    pub author: Author,
+    /// How many commits changing those files they authored or co-authored
+    pub commits: usize,
+}
+
+/// Ranks the co-authors by how many of `commits` they authored or co-authored
+///
+/// Ties go to whoever worked on the files most recently, `commits` being newest first.
+/// `user_email` is left out, as there's no point in suggesting yourself.
+pub fn get_suggestions(
+    commits: &[CommitInfo],
+    coauthors: &LinkedHashMap<String, Author>,
+    user_email: &str,
+) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+
+    for commit in commits {
+        let mut people = vec![commit.author.clone()];
+        people.extend(commit.coauthors());
+
+        for (initials, author) in coauthors {
+            let email = author.email.to_lowercase();
+            if email == user_email.to_lowercase()
+                || !people
+                    .iter()
+                    .any(|person| person.email.to_lowercase() == email)
+            {
+                continue;
+            }
+
+            match suggestions
+                .iter_mut()
+                .find(|suggestion| &suggestion.initials == initials)
+            {
+                Some(suggestion) => suggestion.commits += 1,
+                None => suggestions.push(Suggestion {
+                    initials: initials.to_string(),
+                    author: author.clone(),
+                    commits: 1,
+                }),
+            }
+        }
+    }
+
+    // a stable sort keeps the most recent first among ties
+    suggestions.sort_by_key(|suggestion| Reverse(suggestion.commits));
+    suggestions
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns co-authors who worked on the staged and modified files, best match first
+    pub fn get_suggested_coauthors(&self) -> Result<Vec<Suggestion>> {
+        let paths = self.get_changed_paths()?;
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let commits = self.get_commits_touching(&paths, SUGGEST_DEPTH)?;
+        let user_email = self.get_git_config_value("user.email")?;
+        Ok(get_suggestions(
+            &commits,
+            &self.get_all_coauthors()?,
+            user_email.as_deref().unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{author, commit, get_git_mob};
+
+    #[test]
+    fn test_get_suggestions() {
+        let gm = get_git_mob();
+        let coauthors = gm.get_all_coauthors().unwrap();
+
+        let commits = [
+            commit(
+                "",
+                author("Someone", "me@example.com"),
+                "Fix\n\nCo-authored-by: C D <CD@example.com>\n",
+            ),
+            commit("", author("Someone", "ab@example.com"), "Add\n"),
+            commit("", author("Someone", "ef@example.com"), "Tweak\n"),
+        ];
+        let suggestions = get_suggestions(&commits, &coauthors, "me@example.com");
+        // tied, so the most recent comes first
+        assert_eq!(
+            vec![("cd", 1), ("ab", 1)],
+            suggestions
+                .iter()
+                .map(|suggestion| (suggestion.initials.as_str(), suggestion.commits))
+                .collect::<Vec<_>>()
+        );
+
+        let commits = [
+            commit("", author("Someone", "cd@example.com"), "Fix\n"),
+            commit("", author("Someone", "ab@example.com"), "Add\n"),
+            commit("", author("Someone", "ab@example.com"), "Add more\n"),
+        ];
+        let suggestions = get_suggestions(&commits, &coauthors, "CD@example.com");
+        assert_eq!(
+            vec![Suggestion {
+                initials: String::from("ab"),
+                author: coauthors["ab"].clone(),
+                commits: 2,
+            }],
+            suggestions
+        );
+    }
+}
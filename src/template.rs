@@ -0,0 +1,233 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::message::CommitMessage;
+use crate::{
+    get_template_value, git_config, to_config_path, GitMob, MobScope, GITINITIALS, GITMESSAGE,
+    MOB_STATE, TIMER_STATE,
+};
+use dirs::home_dir;
+use gix_config::Source;
+use std::path::{Path, PathBuf};
+
+/// The key that keeps the `commit.template` that was set before the mob's
+pub const PREVIOUS_TEMPLATE: &str = "mob.previousTemplate";
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the config file the mob's `commit.template` is set in, for the given scope
+    pub(crate) fn get_template_config(&self, scope: MobScope) -> Result<(PathBuf, Source)> {
+        match scope {
+            MobScope::Local => {
+                let repo = self.get_repo()?;
+                if self.is_per_worktree(&repo) {
+                    Ok((repo.path().join("config.worktree"), Source::Worktree))
+                } else {
+                    Ok((repo.common_dir().join("config"), Source::Local))
+                }
+            }
+            MobScope::Global => Ok((git_config::global_path()?, Source::User)),
+        }
+    }
+
+    /// Returns whether a `commit.template` value points at one of the mob's gitmessage files
+    pub(crate) fn is_mob_template(&self, template: &str) -> Result<bool> {
+        let gitmessage_path = self.get_scope_path(MobScope::Local, GITMESSAGE)?;
+        let work_dir = self
+            .get_repo()
+            .ok()
+            .and_then(|repo| repo.work_dir().map(Path::to_path_buf));
+        Ok([
+            get_template_value(work_dir.as_deref(), &gitmessage_path),
+            to_config_path(&gitmessage_path),
+            to_config_path(&self.get_scope_path(MobScope::Global, GITMESSAGE)?),
+        ]
+        .iter()
+        .any(|mob_template| mob_template == template))
+    }
+
+    /// Returns the user's own `commit.template` that the mob's template replaces for the given
+    /// scope, if any
+    ///
+    /// A mob in a repository also replaces the global template.
+    pub fn get_previous_template(&self, scope: MobScope) -> Result<Option<String>> {
+        let (config_path, source) = self.get_template_config(scope)?;
+
+        if let Some(previous) = git_config::get_value(&config_path, source, PREVIOUS_TEMPLATE)? {
+            return Ok(Some(previous));
+        }
+        if let Some(template) = git_config::get_value(&config_path, source, "commit.template")? {
+            if !self.is_mob_template(&template)? {
+                return Ok(Some(template));
+            }
+        }
+
+        match scope {
+            MobScope::Local => self.get_previous_template(MobScope::Global),
+            MobScope::Global => Ok(None),
+        }
+    }
+
+    /// Returns the content of the user's own template for the given scope, which goes above the
+    /// trailers in the gitmessage file
+    pub(crate) fn get_previous_template_content(&self, scope: MobScope) -> Result<String> {
+        let Some(template) = self.get_previous_template(scope)? else {
+            return Ok(String::new());
+        };
+
+        // like git, "~/" is the home directory and relative paths are from the worktree's root
+        let path = match template.strip_prefix("~/") {
+            Some(path) => home_dir()
+                .ok_or_else(|| GitMobError::Io(String::from("Unable to find your home directory")))?
+                .join(path),
+            None => match self
+                .get_repo()
+                .ok()
+                .and_then(|repo| repo.work_dir().map(Path::to_path_buf))
+            {
+                Some(work_dir) => work_dir.join(&template),
+                None => PathBuf::from(&template),
+            },
+        };
+
+        // git itself complains about a template it can't read when committing
+        Ok(self.file_actions.read(&path).unwrap_or_default())
+    }
+
+    /// Restores the `commit.template` that was set before the mob in the repository, returning
+    /// whether there was one
+    pub(crate) fn restore_git_template(&self) -> Result<bool> {
+        let (config_path, source) = self.get_template_config(MobScope::Local)?;
+        restore_git_template_config(&config_path, source)
+    }
+
+    /// Removes the mob from the repository: its files, and its `commit.template`, restoring the
+    /// template that was set before it
+    ///
+    /// Returns the files that were removed.
+    pub fn uninstall(&self) -> Result<Vec<PathBuf>> {
+        let repo = self.get_repo()?;
+
+        // the mob may have been kept per worktree before
+        for (config_path, source) in [
+            (repo.common_dir().join("config"), Source::Local),
+            (repo.path().join("config.worktree"), Source::Worktree),
+        ] {
+            if !restore_git_template_config(&config_path, source)? {
+                if let Some(template) =
+                    git_config::get_value(&config_path, source, "commit.template")?
+                {
+                    if self.is_mob_template(&template)? {
+                        git_config::remove_value(&config_path, source, "commit.template")?;
+                    }
+                }
+            }
+        }
+
+        let mut removed = vec![];
+        for file_name in [GITMESSAGE, GITINITIALS, MOB_STATE, TIMER_STATE] {
+            let path = self.get_scope_path(MobScope::Local, file_name)?;
+            if self.file_actions.read(&path).is_ok() {
+                self.file_actions.remove(&path).map_err(GitMobError::Io)?;
+                removed.push(std::path::absolute(&path).unwrap_or(path));
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Restores the `commit.template` kept in `mob.previousTemplate`, returning whether there was one
+pub(crate) fn restore_git_template_config(config_path: &Path, source: Source) -> Result<bool> {
+    match git_config::get_value(config_path, source, PREVIOUS_TEMPLATE)? {
+        Some(previous) => {
+            git_config::set_value(config_path, source, "commit.template", &previous)?;
+            git_config::remove_value(config_path, source, PREVIOUS_TEMPLATE)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Returns the gitmessage file's content, the user's template with the trailers merged into its
+/// trailer block, before any comments that start with `comment`
+pub(crate) fn format_gitmessage(template: &str, trailers: &[String], comment: &str) -> String {
+    let template = template.trim_end();
+    if !trailers.is_empty() {
+        // an empty template leaves room for the subject
+        CommitMessage::parse(template, Some(comment))
+            .merge(trailers)
+            .trim_end_matches('\n')
+            .to_string()
+    } else if !template.is_empty() {
+        format!("{template}\n")
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_format_gitmessage() {
+        let trailers = [String::from("Co-authored-by: A B <ab@example.com>")];
+
+        assert_eq!("", format_gitmessage("", &[], "#"));
+        assert_eq!(
+            "\n\nCo-authored-by: A B <ab@example.com>",
+            format_gitmessage("", &trailers, "#")
+        );
+        assert_eq!(
+            "feat: \n\n# Checklist\n",
+            format_gitmessage("feat: \n\n# Checklist\n\n", &[], "#")
+        );
+
+        // git's comments stay at the end, with the configured comment prefix
+        assert_eq!(
+            "feat: \n\nCo-authored-by: A B <ab@example.com>\n\n# Checklist",
+            format_gitmessage("feat: \n\n# Checklist\n", &trailers, "#")
+        );
+        assert_eq!(
+            "feat: \n\n# Checklist\n\nCo-authored-by: A B <ab@example.com>",
+            format_gitmessage("feat: \n\n# Checklist\n", &trailers, ";")
+        );
+
+        // the template's own sign-off stays last
+        assert_eq!(
+            "\n\nCo-authored-by: A B <ab@example.com>\nSigned-off-by: Me <me@example.com>",
+            format_gitmessage("\n\nSigned-off-by: Me <me@example.com>\n", &trailers, "#")
+        );
+    }
+
+    #[test]
+    fn test_restore_git_template_config() {
+        let dir = tempdir().unwrap();
+        let config_file_path = dir.path().join("config");
+        fs::write(
+            &config_file_path,
+            "[commit]\n\ttemplate = ~/.team-template\n",
+        )
+        .unwrap();
+
+        let gm = get_git_mob();
+        gm.set_git_template_config(&config_file_path, Source::Local, ".git/.gitmessage")
+            .unwrap();
+        // setting the mob again keeps the user's template
+        gm.set_git_template_config(&config_file_path, Source::Local, ".git/.gitmessage")
+            .unwrap();
+        assert_eq!(
+            Some(String::from("~/.team-template")),
+            git_config::get_value(&config_file_path, Source::Local, PREVIOUS_TEMPLATE).unwrap()
+        );
+
+        assert!(restore_git_template_config(&config_file_path, Source::Local).unwrap());
+        assert_eq!(
+            "[commit]\n\ttemplate = ~/.team-template\n",
+            fs::read_to_string(&config_file_path).unwrap()
+        );
+        assert!(!restore_git_template_config(&config_file_path, Source::Local).unwrap());
+    }
+}
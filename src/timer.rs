@@ -0,0 +1,336 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::expiry::parse_duration;
+use crate::file_actions::FileActions;
+use crate::{GitMob, MobScope, TIMER_STATE};
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+/// The longest a driver can drive, in days, which keeps the times the timer works out in range
+pub const MAX_EVERY_DAYS: i64 = 7;
+
+/// Returns an error if `every` isn't between a second and `MAX_EVERY_DAYS`
+fn check_every(every: Duration) -> std::result::Result<(), String> {
+    if every < Duration::seconds(1) || every > Duration::days(MAX_EVERY_DAYS) {
+        return Err(format!(
+            "a driver should drive for between 1s and {MAX_EVERY_DAYS}d"
+        ));
+    }
+    Ok(())
+}
+
+/// Parses how long each driver drives, such as "10m", see `parse_duration`
+pub fn parse_every(s: &str) -> std::result::Result<Duration, String> {
+    let every = parse_duration(s)?;
+    check_every(every).map_err(|error| format!("\"{}\" is out of range, {error}", s.trim()))?;
+    Ok(every)
+}
+
+fn out_of_range() -> GitMobError {
+    GitMobError::Io(String::from(
+        "The timer is out of range, restart it with \"git mob timer start\" or stop it with \"git mob timer stop\"",
+    ))
+}
+
+/// A rotation timer, kept next to the mob's gitinitials file
+///
+/// Only when it started is saved, the current driver is worked out from the time, so the timer
+/// keeps going without a running process.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Timer {
+    /// When the first driver started driving
+    pub started: DateTime<FixedOffset>,
+    /// How long each driver drives, in seconds
+    pub every: i64,
+    /// The initials of the drivers, in the order they drive
+    pub order: Vec<String>,
+}
+
+/// Who is driving at some point in time, as printed with `--json`
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Rotation {
+    pub driver: String,
+    pub next: String,
+    /// How many rotations there have been since the timer started
+    pub turn: i64,
+    /// When the next driver takes over
+    pub rotates_at: DateTime<FixedOffset>,
+}
+
+impl Timer {
+    pub fn new(started: DateTime<FixedOffset>, every: Duration, order: Vec<String>) -> Self {
+        Timer {
+            started,
+            every: every.num_seconds().max(1),
+            order,
+        }
+    }
+
+    fn turn(&self, now: DateTime<FixedOffset>) -> i64 {
+        // a clock that went back stays on the first driver
+        (now - self.started).num_seconds().max(0) / self.every
+    }
+
+    /// Returns how long `turns` turns take, if that's in range
+    fn turns(&self, turns: i64) -> Option<Duration> {
+        turns
+            .checked_mul(self.every)
+            .and_then(Duration::try_seconds)
+    }
+
+    /// Returns who is driving at `now`
+    ///
+    /// A timer edited by hand can be too far out for its times, which is an error.
+    pub fn rotation(&self, now: DateTime<FixedOffset>) -> Result<Rotation> {
+        let turn = self.turn(now);
+        let driver = |turn: i64| self.order[turn as usize % self.order.len()].clone();
+        let rotates_at = self
+            .turns(turn + 1)
+            .and_then(|duration| self.started.checked_add_signed(duration))
+            .ok_or_else(out_of_range)?;
+        Ok(Rotation {
+            driver: driver(turn),
+            next: driver(turn + 1),
+            turn,
+            rotates_at,
+        })
+    }
+
+    /// Hands over to the next driver at `now`, who then gets a whole turn
+    pub fn skip(&mut self, now: DateTime<FixedOffset>) -> Result<()> {
+        let turn = self.turn(now) + 1;
+        self.started = self
+            .turns(turn)
+            .and_then(|duration| now.checked_sub_signed(duration))
+            .ok_or_else(out_of_range)?;
+        Ok(())
+    }
+}
+
+/// Formats a duration for people, such as "4m 05s", "1h 10m" or "30s"
+pub fn format_remaining(remaining: Duration) -> String {
+    // rounded up, so the last second shows as "1s" rather than "0s"
+    let seconds = (remaining.num_milliseconds().max(0) + 999) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m {seconds:02}s"),
+        _ => format!("{hours}h {minutes:02}m"),
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the scope of the mob the timer rotates, which has to be set
+    fn get_timer_scope(&self) -> Result<MobScope> {
+        match self.get_active_scope()? {
+            Some(scope) if !self.get_active_initials()?.is_empty() => Ok(scope),
+            _ => Err(GitMobError::Io(String::from(
+                "There is no mob to rotate, run \"git mob <initials>\" first",
+            ))),
+        }
+    }
+
+    /// Starts rotating the driver every `every`, in the given order or by default the mob's
+    ///
+    /// The order can name groups and co-authors who aren't in the mob, such as yourself.
+    pub fn start_timer(&self, every: Duration, order: &[String]) -> Result<Timer> {
+        check_every(every)
+            .map_err(|error| GitMobError::Io(format!("Unable to start the timer, {error}")))?;
+        let scope = self.get_timer_scope()?;
+        let order = if order.is_empty() {
+            self.get_active_initials()?
+        } else {
+            let order = self.expand_groups(order)?;
+            self.check_group_members(&order)?;
+            order
+        };
+
+        let timer = Timer::new(self.clock.now(), every, order);
+        self.write_timer(scope, &timer)?;
+        Ok(timer)
+    }
+
+    /// Returns the timer of the mob that applies here, if it's running
+    ///
+    /// A timer edited by hand with a turn that's out of range is an error.
+    pub fn get_timer(&self) -> Result<Option<Timer>> {
+        let Some(scope) = self.get_active_scope()? else {
+            return Ok(None);
+        };
+        let timer = self
+            .file_actions
+            .read(&self.get_scope_path(scope, TIMER_STATE)?)
+            .ok()
+            .and_then(|timer| serde_json::from_str::<Timer>(&timer).ok())
+            .filter(|timer| !timer.order.is_empty());
+        if let Some(timer) = &timer {
+            check_every(Duration::seconds(timer.every)).map_err(|_| out_of_range())?;
+        }
+        Ok(timer)
+    }
+
+    /// Returns who is driving now, if the timer is running
+    pub fn get_rotation(&self) -> Result<Option<Rotation>> {
+        self.get_timer()?
+            .map(|timer| timer.rotation(self.clock.now()))
+            .transpose()
+    }
+
+    /// Hands over to the next driver now, returning the timer if it's running
+    pub fn skip_driver(&self) -> Result<Option<Timer>> {
+        let Some(mut timer) = self.get_timer()? else {
+            return Ok(None);
+        };
+        timer.skip(self.clock.now())?;
+        self.write_timer(self.get_timer_scope()?, &timer)?;
+        Ok(Some(timer))
+    }
+
+    /// Stops the timer, returning whether it was running
+    pub fn stop_timer(&self) -> Result<bool> {
+        // a timer that's out of range is running as far as stopping it goes
+        let running = !matches!(self.get_timer(), Ok(None));
+        if let Some(scope) = self.get_active_scope()? {
+            self.remove_timer(scope)?;
+        }
+        Ok(running)
+    }
+
+    fn write_timer(&self, scope: MobScope, timer: &Timer) -> Result<()> {
+        let timer = serde_json::to_string(timer)
+            .map_err(|error| GitMobError::Io(format!("Unable to save the timer: {error}")))?;
+        self.file_actions
+            .write(&self.get_scope_path(scope, TIMER_STATE)?, &timer)
+            .map_err(GitMobError::Io)
+    }
+
+    pub(crate) fn remove_timer(&self, scope: MobScope) -> Result<()> {
+        self.file_actions
+            .remove(&self.get_scope_path(scope, TIMER_STATE)?)
+            .map_err(GitMobError::Io)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+
+    fn time(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    fn strings(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut timer = Timer::new(
+            time("2024-01-01T09:00:00+00:00"),
+            Duration::minutes(10),
+            strings(&["ab", "cd", "ef"]),
+        );
+
+        let rotation = timer.rotation(time("2024-01-01T09:09:59+00:00")).unwrap();
+        assert_eq!(
+            ("ab", "cd", 0),
+            (&*rotation.driver, &*rotation.next, rotation.turn)
+        );
+        assert_eq!(time("2024-01-01T09:10:00+00:00"), rotation.rotates_at);
+
+        let rotation = timer.rotation(time("2024-01-01T09:25:00+00:00")).unwrap();
+        assert_eq!(
+            ("ef", "ab", 2),
+            (&*rotation.driver, &*rotation.next, rotation.turn)
+        );
+
+        // the next driver gets a whole turn
+        timer.skip(time("2024-01-01T09:25:00+00:00")).unwrap();
+        let rotation = timer.rotation(time("2024-01-01T09:25:00+00:00")).unwrap();
+        assert_eq!(
+            ("ab", "cd", 3),
+            (&*rotation.driver, &*rotation.next, rotation.turn)
+        );
+        assert_eq!(time("2024-01-01T09:35:00+00:00"), rotation.rotates_at);
+
+        let rotation = timer.rotation(time("2024-01-01T08:00:00+00:00")).unwrap();
+        assert_eq!("ab", rotation.driver);
+    }
+
+    #[test]
+    fn test_timer_out_of_range() {
+        assert!(parse_every("7d").is_ok());
+        assert!(parse_every("8d").is_err());
+        assert!(parse_every("100000000d").is_err());
+        assert!(parse_every("0s").is_err());
+
+        // a timer saved before intervals were limited is an error rather than a panic
+        let mut timer = Timer {
+            started: time("2024-01-01T09:00:00+00:00"),
+            every: Duration::days(100_000_000).num_seconds(),
+            order: strings(&["ab", "cd"]),
+        };
+        let now = time("2024-01-01T09:10:00+00:00");
+        assert!(timer.rotation(now).is_err());
+        assert!(timer.skip(now).is_err());
+
+        let gm = get_git_mob();
+        gm.write_gitmessage(&strings(&["ab", "cd"])).unwrap();
+        assert!(gm.start_timer(Duration::days(8), &[]).is_err());
+        assert_eq!(None, gm.get_timer().unwrap());
+
+        // nor is one edited by hand to drive for no time, or less than none
+        let path = gm.get_scope_path(MobScope::Local, TIMER_STATE).unwrap();
+        for every in [0, -600, Duration::days(8).num_seconds()] {
+            gm.file_actions
+                .write(
+                    &path,
+                    &format!(
+                        r#"{{"started":"2024-01-01T09:00:00+00:00","every":{every},"order":["ab","cd"]}}"#
+                    ),
+                )
+                .unwrap();
+            assert!(gm.get_timer().is_err());
+            assert!(gm.get_rotation().is_err());
+            assert!(gm.skip_driver().is_err());
+        }
+        assert!(gm.stop_timer().unwrap());
+        assert_eq!(None, gm.get_timer().unwrap());
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!("30s", format_remaining(Duration::seconds(30)));
+        assert_eq!("4m 05s", format_remaining(Duration::seconds(245)));
+        assert_eq!("1h 10m", format_remaining(Duration::minutes(70)));
+        assert_eq!("1s", format_remaining(Duration::milliseconds(200)));
+        assert_eq!("0s", format_remaining(Duration::seconds(-5)));
+    }
+
+    #[test]
+    fn test_timer() {
+        let gm = get_git_mob();
+
+        gm.write_gitmessage(&[]).unwrap();
+        assert!(gm.start_timer(Duration::minutes(10), &[]).is_err());
+
+        gm.write_gitmessage(&strings(&["ab", "cd"])).unwrap();
+        gm.start_timer(Duration::minutes(10), &strings(&["cd", "ab"]))
+            .unwrap();
+        gm.clock.set("2024-01-01T09:10:00+00:00");
+        assert_eq!("ab", gm.get_rotation().unwrap().unwrap().driver);
+
+        gm.skip_driver().unwrap();
+        assert_eq!("cd", gm.get_rotation().unwrap().unwrap().driver);
+
+        // going solo stops the timer
+        gm.write_gitmessage(&[]).unwrap();
+        assert_eq!(None, gm.get_timer().unwrap());
+
+        gm.write_gitmessage(&strings(&["ab"])).unwrap();
+        gm.start_timer(Duration::minutes(10), &[]).unwrap();
+        assert!(gm.stop_timer().unwrap());
+        assert!(!gm.stop_timer().unwrap());
+    }
+}
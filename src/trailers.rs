@@ -0,0 +1,187 @@
+use crate::clock::Clock;
+use crate::error::{GitMobError, Result};
+use crate::file_actions::FileActions;
+use crate::message::Trailer;
+use crate::{Author, GitMob};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TRAILER_KEY: &str = "Co-authored-by";
+const DEFAULT_TRAILER_FORMAT: &str = "{key}: {name} <{email}>";
+
+/// The trailer added for each co-author, set with `mob.trailer` and `mob.trailerFormat`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TrailerFormat {
+    /// The trailer's key, such as "Co-authored-by" or "Signed-off-by"
+    pub key: String,
+    /// The whole line, where "{key}", "{name}", "{email}" and "{initials}" are replaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+impl Default for TrailerFormat {
+    fn default() -> Self {
+        TrailerFormat {
+            key: String::from(DEFAULT_TRAILER_KEY),
+            format: None,
+        }
+    }
+}
+
+impl TrailerFormat {
+    /// Returns the format with the key and line format that are given replaced, making sure the
+    /// lines are still trailers
+    pub fn with(
+        &self,
+        key: Option<&str>,
+        format: Option<&str>,
+    ) -> std::result::Result<Self, String> {
+        let trailer = TrailerFormat {
+            key: key.unwrap_or(&self.key).to_string(),
+            format: format.map(String::from).or_else(|| self.format.clone()),
+        };
+
+        if Trailer::parse(&format!("{}:", trailer.key)).is_none() {
+            return Err(format!(
+                "\"{}\" should be a trailer key like \"{DEFAULT_TRAILER_KEY}\"",
+                trailer.key
+            ));
+        }
+        if let Some(format) = &trailer.format {
+            if Trailer::parse(&format.replace("{key}", &trailer.key)).is_none() {
+                return Err(format!(
+                    "\"{format}\" should start with a trailer key and a colon, like \"{DEFAULT_TRAILER_FORMAT}\""
+                ));
+            }
+        }
+        Ok(trailer)
+    }
+
+    /// Returns the trailer line for a co-author
+    pub fn format(&self, initials: &str, author: &Author) -> String {
+        let values = [
+            ("{key}", self.key.as_str()),
+            ("{name}", &author.name),
+            ("{email}", &author.email),
+            ("{initials}", initials),
+        ];
+
+        // replaced in one pass, so a name can't contain a placeholder that is then replaced
+        let mut line = String::new();
+        let mut rest = self.format.as_deref().unwrap_or(DEFAULT_TRAILER_FORMAT);
+        while let Some(start) = rest.find('{') {
+            line.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match values
+                .iter()
+                .find(|(placeholder, _)| rest.starts_with(placeholder))
+            {
+                Some((placeholder, value)) => {
+                    line.push_str(value);
+                    rest = &rest[placeholder.len()..];
+                }
+                None => {
+                    line.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        line.push_str(rest);
+        line
+    }
+}
+
+impl<T: FileActions, C: Clock> GitMob<T, C> {
+    /// Returns the configured trailer format, from the repository's or the global git config
+    pub fn get_trailer_format(&self) -> Result<TrailerFormat> {
+        let key = self.get_git_config_value("mob.trailer")?;
+        let format = self.get_git_config_value("mob.trailerFormat")?;
+        TrailerFormat::default()
+            .with(key.as_deref(), None)
+            .map_err(|error| GitMobError::GitConfig(format!("mob.trailer: {error}")))?
+            .with(None, format.as_deref())
+            .map_err(|error| GitMobError::GitConfig(format!("mob.trailerFormat: {error}")))
+    }
+
+    /// Returns the trailer format the active mob was set with, or the configured one for a mob
+    /// set before the format was kept
+    pub fn get_active_trailer_format(&self) -> Result<TrailerFormat> {
+        let state = match self.get_active_scope()? {
+            Some(scope) => self.get_mob_state(scope)?,
+            None => None,
+        };
+        match state.and_then(|state| state.trailer) {
+            Some(trailer) => Ok(trailer),
+            None => self.get_trailer_format(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::get_git_mob;
+    use crate::MobScope;
+
+    #[test]
+    fn test_format_trailer() {
+        let author = Author {
+            name: String::from("A B"),
+            email: String::from("ab@example.com"),
+        };
+
+        assert_eq!(
+            "Co-authored-by: A B <ab@example.com>",
+            TrailerFormat::default().format("ab", &author)
+        );
+
+        let trailer = TrailerFormat::default()
+            .with(Some("Signed-off-by"), None)
+            .unwrap();
+        assert_eq!(
+            "Signed-off-by: A B <ab@example.com>",
+            trailer.format("ab", &author)
+        );
+
+        let trailer = TrailerFormat::default()
+            .with(
+                None,
+                Some("Pair-Programmed-With: {name} ({initials}) {email} {unknown}"),
+            )
+            .unwrap();
+        assert_eq!(
+            "Pair-Programmed-With: A B (ab) ab@example.com {unknown}",
+            trailer.format("ab", &author)
+        );
+
+        assert!(TrailerFormat::default()
+            .with(Some("Paired with"), None)
+            .is_err());
+        assert!(TrailerFormat::default()
+            .with(None, Some("{name} <{email}>"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_mob_keeps_trailer_format() {
+        let gm = get_git_mob();
+
+        let trailer = TrailerFormat::default()
+            .with(Some("Signed-off-by"), None)
+            .unwrap();
+        gm.write_gitmessage_with_trailer(&[String::from("ab")], &trailer)
+            .unwrap();
+
+        assert_eq!(
+            "\n\nSigned-off-by: A B <ab@example.com>",
+            gm.get_gitmessage().unwrap()
+        );
+        assert_eq!(trailer, gm.get_active_trailer_format().unwrap());
+        assert_eq!(
+            vec![String::from("Signed-off-by: C D <cd@example.com>")],
+            gm.get_coauthor_trailers(&[String::from("cd")]).unwrap()
+        );
+
+        gm.write_gitmessage(&[]).unwrap();
+        assert_eq!(None, gm.get_mob_state(MobScope::Local).unwrap());
+    }
+}